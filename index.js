@@ -1,2 +1,41 @@
 require('./style.css')
-import('./pkg').catch(console.error)
\ No newline at end of file
+import('./pkg')
+    .then(({ PendulumApp }) => {
+        // Double pendulum released from rest with the first rod raised
+        const canvas = document.getElementById('render-canvas')
+        const app = new PendulumApp(canvas, 'double')
+        app.set_initial_state([Math.PI / 4, 0, 0, 0])
+        app.set_throw(true)
+        app.set_lyapunov(true)
+        app.set_phase_plot(2400, true)
+        app.set_trail(480, '#00aaff', [])
+        app.set_trail_colormap('viridis', 8)
+        app.start()
+
+        // Playback controls
+        const pause = document.getElementById('pause')
+        pause.onclick = () => {
+            if (app.is_paused()) {
+                app.start()
+                pause.textContent = 'Pause'
+            } else {
+                app.pause()
+                pause.textContent = 'Resume'
+            }
+        }
+        document.getElementById('step').onclick = () => app.step()
+        document.getElementById('reset').onclick = () => app.reset()
+
+        // Offer the recorded trajectory as a file download
+        const download = (format) => {
+            const url = URL.createObjectURL(app.export_trajectory(format))
+            const link = document.createElement('a')
+            link.href = url
+            link.download = `trajectory.${format}`
+            link.click()
+            setTimeout(() => URL.revokeObjectURL(url))
+        }
+        document.getElementById('download-csv').onclick = () => download('csv')
+        document.getElementById('download-jsonl').onclick = () => download('jsonl')
+    })
+    .catch(console.error)
@@ -2,15 +2,137 @@ use std::{cell::RefCell, rc::Rc};
 use wasm_bindgen::prelude::*;
 use web_sys::window;
 
+use crate::sim::FrameCtx;
+
 const MILLIS_PER_SEC: f64 = 1000.0;
 
-#[allow(dead_code)]
-/// Context for a single animation frame,
-/// containing the frame number, delta time, and timestamp.
-pub struct FrameCtx {
-    pub frame: u32,
-    pub dt: f64,
-    pub ts: f64,
+/// Frame time, in seconds, reported when single-stepping
+/// before any frame has been timed.
+const DEFAULT_FRAME_DT: f64 = 1.0 / 60.0;
+
+/// Returns the current time in seconds, on the same
+/// clock as `requestAnimationFrame` timestamps.
+fn now() -> f64 {
+    window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now() / MILLIS_PER_SEC)
+}
+
+/// Shared state of a running animation loop.
+///
+/// # Fields
+/// * `frame_count` - Number of frames run so far.
+/// * `last_ts` - Timestamp of the last frame, in seconds.
+/// * `last_dt` - Delta time of the last frame, used when single-stepping.
+/// * `paused_at` - Time the loop was paused at, if paused.
+/// * `request_id` - Id of the pending animation frame request, if any.
+/// * `closure` - The frame callback, dropped when the loop is cancelled.
+struct LoopState {
+    frame_count: u32,
+    last_ts: Option<f64>,
+    last_dt: f64,
+    paused_at: Option<f64>,
+    request_id: Option<i32>,
+    closure: Option<Closure<dyn FnMut(f64)>>,
+}
+
+impl LoopState {
+    /// Requests the next animation frame, unless one is already
+    /// pending or the loop has been cancelled.
+    fn request(&mut self) -> Result<(), JsValue> {
+        if let (None, Some(closure)) = (self.request_id, &self.closure) {
+            let id = window()
+                .unwrap()
+                .request_animation_frame(closure.as_ref().unchecked_ref())?;
+            self.request_id = Some(id);
+        }
+        Ok(())
+    }
+
+    /// Cancels the pending animation frame request, if any.
+    fn cancel_request(&mut self) -> Result<(), JsValue> {
+        if let Some(id) = self.request_id.take() {
+            window().unwrap().cancel_animation_frame(id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Handle for controlling a running animation loop.
+///
+/// Dropping the handle leaves the loop running. Clones
+/// of the handle all control the same loop.
+#[derive(Clone)]
+pub struct AnimationHandle(Rc<RefCell<LoopState>>);
+
+impl AnimationHandle {
+    /// Pauses the loop. Does nothing if already paused or cancelled.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the pending frame cannot be cancelled.
+    pub fn pause(&self) -> Result<(), JsValue> {
+        let mut state = self.0.borrow_mut();
+        if state.paused_at.is_some() || state.closure.is_none() {
+            return Ok(());
+        }
+        state.cancel_request()?;
+        state.paused_at = Some(now());
+        Ok(())
+    }
+
+    /// Resumes a paused loop. The paused interval is excluded
+    /// from the next frame's delta time.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the animation frame request fails.
+    pub fn resume(&self) -> Result<(), JsValue> {
+        let mut state = self.0.borrow_mut();
+        if let Some(paused_at) = state.paused_at.take() {
+            if let Some(last_ts) = &mut state.last_ts {
+                *last_ts += now() - paused_at;
+            }
+            state.request()?;
+        }
+        Ok(())
+    }
+
+    /// Runs exactly one frame of a paused loop, with the same
+    /// delta time as the last frame. Does nothing if not paused.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the animation frame request fails.
+    pub fn step(&self) -> Result<(), JsValue> {
+        let mut state = self.0.borrow_mut();
+        if state.paused_at.is_some() {
+            state.request()?;
+        }
+        Ok(())
+    }
+
+    /// Stops the loop for good, cancelling the pending
+    /// frame and dropping the frame callback.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the pending frame cannot be cancelled.
+    pub fn cancel(&self) -> Result<(), JsValue> {
+        let mut state = self.0.borrow_mut();
+        state.cancel_request()?;
+        state.paused_at = None;
+        let closure = state.closure.take();
+        drop(state);
+        drop(closure);
+        Ok(())
+    }
+
+    /// Returns whether the loop is paused.
+    pub fn is_paused(&self) -> bool {
+        self.0.borrow().paused_at.is_some()
+    }
+
+    /// Returns whether the loop has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.borrow().closure.is_none()
+    }
 }
 
 /// requestAnimationFrame.
@@ -20,75 +142,76 @@ pub struct FrameCtx {
 /// It takes a callback function that is called for each frame with
 /// a `FrameCtx` containing frame information.
 pub struct AnimationLoopRunner<F: FnMut(&FrameCtx) + 'static> {
-    frame_count: u32,
-    last_ts: Option<f64>,
     callback: F,
 }
 
 impl<F: FnMut(&FrameCtx) + 'static> AnimationLoopRunner<F> {
     /// Creates a new AnimationLoopRunner with the given callback function.
     pub fn new(callback: F) -> Self {
-        Self {
-            frame_count: 0,
-            last_ts: None,
-            callback,
-        }
+        Self { callback }
     }
 
     /// Starts the animation loop. Continually
     /// requests new animation frame from browser
-    pub fn run(mut self) -> Result<(), JsValue> {
-        // Helper type for callback pointer.
-        // Shared Ownership, interior mutability
-        // And the ability to be undefined at runtime.
-        type CallbackPtr = Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>>;
-
-        // Create two pointers for underlying callback. One to store inside
-        // callback to be recalled and one for outside to call the first time
-        let inner_ptr: CallbackPtr = Rc::new(RefCell::new(None));
-        let outer_ptr: CallbackPtr = Rc::clone(&inner_ptr);
-
-        // Define the callback in one of the pointers, moving the other one
-        *outer_ptr.borrow_mut() = Some(Closure::new(move |ts_ms| {
-            // Initialize current frame (time step, dt, and frame count)
-            let ts = ts_ms / MILLIS_PER_SEC;
-            let dt = ts - self.last_ts.unwrap_or(ts);
-            let frame = FrameCtx {
-                frame: self.frame_count,
-                dt,
-                ts,
+    /// until cancelled through the returned handle.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the first animation frame request fails.
+    pub fn run(mut self) -> Result<AnimationHandle, JsValue> {
+        // Shared state, held by both the handle and the callback.
+        // The callback owning the state which owns the callback keeps
+        // the loop alive until cancelling breaks the cycle.
+        let state = Rc::new(RefCell::new(LoopState {
+            frame_count: 0,
+            last_ts: None,
+            last_dt: DEFAULT_FRAME_DT,
+            paused_at: None,
+            request_id: None,
+            closure: None,
+        }));
+        let inner = Rc::clone(&state);
+
+        // Define the callback, moving the inner pointer
+        state.borrow_mut().closure = Some(Closure::new(move |ts_ms| {
+            // Initialize current frame (time step, dt, and frame count).
+            // Frames run while paused are single steps.
+            let frame = {
+                let mut state = inner.borrow_mut();
+                state.request_id = None;
+                let ts = ts_ms / MILLIS_PER_SEC;
+                let dt = match state.paused_at {
+                    Some(_) => state.last_dt,
+                    None => ts - state.last_ts.unwrap_or(ts),
+                };
+                FrameCtx {
+                    frame: state.frame_count,
+                    dt,
+                    ts,
+                }
             };
 
-            // Call contained callback for frame
+            // Call contained callback for frame, without holding
+            // the state so it may control the loop through a handle
             (self.callback)(&frame);
 
             // Update frame count
-            self.frame_count += 1;
-            self.last_ts = Some(frame.ts);
+            let mut state = inner.borrow_mut();
+            state.frame_count += 1;
+            state.last_ts = Some(frame.ts);
+            if frame.dt > 0.0 {
+                state.last_dt = frame.dt;
+            }
 
-            // Request next animation frame
-            window()
-                .unwrap()
-                .request_animation_frame(
-                    inner_ptr
-                        .borrow()
-                        .as_ref()
-                        .unwrap()
-                        .as_ref()
-                        .unchecked_ref(),
-                )
-                .unwrap();
+            // Request next animation frame, or restart the paused interval
+            if state.paused_at.is_some() {
+                state.paused_at = Some(frame.ts);
+            } else {
+                state.request().unwrap();
+            }
         }));
 
-        // Request the first animation frame with outer pointer
-        window().unwrap().request_animation_frame(
-            outer_ptr
-                .borrow()
-                .as_ref()
-                .unwrap()
-                .as_ref()
-                .unchecked_ref(),
-        )?;
-        Ok(())
+        // Request the first animation frame
+        state.borrow_mut().request()?;
+        Ok(AnimationHandle(state))
     }
 }
@@ -0,0 +1,714 @@
+use std::{
+    cell::{Cell, RefCell},
+    f64::consts::PI,
+    rc::Rc,
+};
+use wasm_bindgen::prelude::*;
+use web_sys::{Blob, CanvasRenderingContext2d, HtmlCanvasElement};
+
+use crate::{
+    anim::AnimationHandle,
+    camera::Camera,
+    chain_pendulum::{ChainPendulum, ChainPendulumParams, Link},
+    color::{Color, Colormap},
+    diagnostics::Diagnostics,
+    double_pendulum::{DoublePendulum, DoublePendulumParams, State},
+    ensemble::Ensemble,
+    friction::Friction,
+    integrator,
+    lyapunov::Lyapunov,
+    pendulum::{Pendulum, PendulumParams},
+    phase::PhasePlot,
+    poincare::PoincareSection,
+    record::{Format, Observe, Recorder, Sample},
+    runner::SimulationRunner,
+    sim::Simulation,
+    trail::Trail,
+    window::WindowCtx,
+};
+
+/// Tolerance used by the adaptive integrator.
+const TOLERANCE: f64 = 1e-9;
+
+/// Maximum number of trajectory samples kept for export,
+/// i.e. five minutes of simulation at 240 steps per second.
+const RECORDER_CAPACITY: usize = 240 * 60 * 5;
+
+/// Default number of double pendulums in an ensemble.
+const ENSEMBLE_MEMBERS: usize = 100;
+
+/// Default difference in radians between the initial
+/// angles of consecutive members of an ensemble.
+const ENSEMBLE_OFFSET: f64 = 1e-6;
+
+/// Simulation types the app can run.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Single,
+    Double,
+    Chain,
+    Ensemble,
+}
+
+/// A launched simulation.
+///
+/// # Fields
+/// * `handle` - Controls the animation loop.
+/// * `sample` - The latest sample of the simulation.
+/// * `exponent` - The latest Lyapunov exponent estimate, if tracked.
+/// * `recorder` - The recorded trajectory.
+struct Running {
+    handle: AnimationHandle,
+    sample: Rc<RefCell<Sample>>,
+    exponent: Rc<Cell<Option<f64>>>,
+    recorder: Rc<RefCell<Recorder>>,
+}
+
+/// Controller for embedding a pendulum simulation in a page.
+///
+/// Configure the simulation with the setters, then `start` it. Settings
+/// take effect on the next `start` after `stop`, or on `reset`.
+#[wasm_bindgen]
+pub struct PendulumApp {
+    canvas: HtmlCanvasElement,
+    ctx: CanvasRenderingContext2d,
+    kind: Kind,
+    lengths: Vec<f64>,
+    masses: Vec<f64>,
+    frictions: Vec<Friction>,
+    initial: Vec<f64>,
+    integrator: String,
+    step: f64,
+    hud: bool,
+    throw: bool,
+    auto_fit: bool,
+    trail: Trail,
+    lyapunov: bool,
+    members: usize,
+    offset: f64,
+    phase_plot: Option<PhasePlot>,
+    poincare: Option<Rc<RefCell<PoincareSection>>>,
+    running: Option<Running>,
+}
+
+#[wasm_bindgen]
+impl PendulumApp {
+    /// Creates a new PendulumApp drawing on the given canvas.
+    ///
+    /// Starts from 1m rods with 1kg bobs, released from rest
+    /// with the first rod raised, integrated with `rk4`.
+    ///
+    /// # Arguments
+    /// * `canvas` - The canvas to draw on.
+    /// * `kind` - `single`, `double`, `chain` (three links) or
+    ///   `ensemble` (100 double pendulums).
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the kind is unknown or
+    /// the canvas has no 2D drawing context.
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas: HtmlCanvasElement, kind: &str) -> Result<PendulumApp, JsValue> {
+        console_error_panic_hook::set_once();
+
+        // Initial conditions
+        let (kind, links, theta) = match kind {
+            "single" => (Kind::Single, 1, PI / 4.0),
+            "double" => (Kind::Double, 2, PI / 4.0),
+            "chain" => (Kind::Chain, 3, PI / 2.0),
+            "ensemble" => (Kind::Ensemble, 2, PI / 2.0),
+            _ => return Err(format!("unknown simulation '{kind}'").into()),
+        };
+        let mut initial = vec![0.0; 2 * links];
+        initial[0] = theta;
+
+        // Canvas rendering context
+        let ctx = canvas
+            .get_context("2d")?
+            .ok_or(JsValue::from("Could not create 2D drawing context!"))?
+            .dyn_into::<CanvasRenderingContext2d>()?;
+
+        Ok(Self {
+            canvas,
+            ctx,
+            kind,
+            lengths: vec![1.0; links],
+            masses: vec![1.0; links],
+            frictions: Vec::new(),
+            initial,
+            integrator: "rk4".into(),
+            step: 1.0 / 240.0,
+            hud: true,
+            throw: false,
+            auto_fit: true,
+            trail: Trail::default().length(0),
+            lyapunov: false,
+            members: ENSEMBLE_MEMBERS,
+            offset: ENSEMBLE_OFFSET,
+            phase_plot: None,
+            poincare: None,
+            running: None,
+        })
+    }
+
+    /// Sets the rod lengths in meters, from the pivot outward.
+    ///
+    /// For a chain this also sets the number of links.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if any length is not positive and finite.
+    pub fn set_lengths(&mut self, lengths: Vec<f64>) -> Result<(), JsValue> {
+        if !lengths.iter().all(|&l| l > 0.0 && l.is_finite()) {
+            return Err("Lengths must be positive and finite!".into());
+        }
+        self.lengths = lengths;
+        Ok(())
+    }
+
+    /// Sets the bob masses in kilograms, from the pivot outward.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if any mass is not positive and finite.
+    pub fn set_masses(&mut self, masses: Vec<f64>) -> Result<(), JsValue> {
+        if !masses.iter().all(|&m| m > 0.0 && m.is_finite()) {
+            return Err("Masses must be positive and finite!".into());
+        }
+        self.masses = masses;
+        Ok(())
+    }
+
+    /// Sets the friction of a joint. Joints default to frictionless.
+    ///
+    /// # Arguments
+    /// * `joint` - Index of the joint, from the pivot outward.
+    /// * `viscous` - Viscous damping coefficient.
+    /// * `coulomb` - Coulomb friction coefficient.
+    /// * `drag` - Air drag coefficient.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if any coefficient is negative or not finite.
+    pub fn set_friction(
+        &mut self,
+        joint: usize,
+        viscous: f64,
+        coulomb: f64,
+        drag: f64,
+    ) -> Result<(), JsValue> {
+        if ![viscous, coulomb, drag]
+            .iter()
+            .all(|&c| c >= 0.0 && c.is_finite())
+        {
+            return Err("Friction must be non-negative and finite!".into());
+        }
+        if self.frictions.len() <= joint {
+            self.frictions.resize(joint + 1, Friction::default());
+        }
+        self.frictions[joint] = Friction {
+            viscous,
+            coulomb,
+            drag,
+        };
+        Ok(())
+    }
+
+    /// Sets the initial state as `(theta_1, omega_1, ..., theta_n, omega_n)`,
+    /// with absolute angles in radians from straight down.
+    pub fn set_initial_state(&mut self, state: Vec<f64>) {
+        self.initial = state;
+    }
+
+    /// Sets the integrator by name, e.g. `rk4` or `rk45-adaptive`.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the integrator is unknown.
+    pub fn set_integrator(&mut self, name: &str) -> Result<(), JsValue> {
+        if !integrator::NAMES.contains(&name) {
+            return Err(format!("unknown integrator '{name}'").into());
+        }
+        self.integrator = name.into();
+        Ok(())
+    }
+
+    /// Sets the fixed physics time step, in seconds.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the step is not positive and finite.
+    pub fn set_step(&mut self, step: f64) -> Result<(), JsValue> {
+        if !(step > 0.0 && step.is_finite()) {
+            return Err("Step must be positive and finite!".into());
+        }
+        self.step = step;
+        Ok(())
+    }
+
+    /// Sets whether to draw the diagnostics heads-up display.
+    pub fn set_hud(&mut self, hud: bool) {
+        self.hud = hud;
+    }
+
+    /// Sets whether a bob dragged with the pointer keeps its velocity
+    /// when released, rather than being let go from rest.
+    pub fn set_throw(&mut self, throw: bool) {
+        self.throw = throw;
+    }
+
+    /// Sets whether the view keeps the whole pendulum fitted to the canvas.
+    ///
+    /// Otherwise the pivot sits a quarter of the way down the canvas.
+    /// Either way, the view can be panned by dragging and zoomed
+    /// with the mouse wheel, which turns auto-fit off.
+    pub fn set_auto_fit(&mut self, auto_fit: bool) {
+        self.auto_fit = auto_fit;
+    }
+
+    /// Sets a fading trail to draw behind the bobs.
+    ///
+    /// # Arguments
+    /// * `length` - Number of physics steps the trail spans, or zero for no trail.
+    /// * `color` - CSS color of the trail.
+    /// * `bobs` - Indices of the bobs to trace, or empty for the outermost bob.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the color can't be parsed.
+    pub fn set_trail(
+        &mut self,
+        length: usize,
+        color: &str,
+        bobs: Vec<usize>,
+    ) -> Result<(), JsValue> {
+        let color: Color = color.parse().map_err(|e: String| JsValue::from(e))?;
+        let trail = Trail::default().length(length).color(color);
+        self.trail = if bobs.is_empty() {
+            trail
+        } else {
+            trail.bobs(bobs)
+        };
+        Ok(())
+    }
+
+    /// Colors the trail set by `set_trail` by the speed of the bobs.
+    ///
+    /// # Arguments
+    /// * `colormap` - Name of the colormap: `viridis`, `magma`, `twilight` or `rainbow`.
+    /// * `max_speed` - The speed, in m/s, mapped to the end of the colormap.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the colormap is unknown or
+    /// the maximum speed is not positive and finite.
+    pub fn set_trail_colormap(&mut self, colormap: &str, max_speed: f64) -> Result<(), JsValue> {
+        let colormap: Colormap = colormap.parse().map_err(|e: String| JsValue::from(e))?;
+        if !(max_speed > 0.0 && max_speed.is_finite()) {
+            return Err("Maximum speed must be positive and finite!".into());
+        }
+        self.trail = self.trail.clone().colormap(colormap, max_speed);
+        Ok(())
+    }
+
+    /// Sets whether to estimate the maximal Lyapunov exponent of a
+    /// double pendulum with a shadow trajectory, shown on the heads-up
+    /// display and read back with `lyapunov_exponent`.
+    pub fn set_lyapunov(&mut self, lyapunov: bool) {
+        self.lyapunov = lyapunov;
+    }
+
+    /// Sets a phase-space plot of each joint's angle against its angular
+    /// velocity, drawn as an inset in the bottom-right corner of the canvas.
+    ///
+    /// # Arguments
+    /// * `length` - Number of physics steps the plot spans, or zero for no plot.
+    /// * `wrap` - Whether to wrap angles to `[-π, π]`.
+    pub fn set_phase_plot(&mut self, length: usize, wrap: bool) {
+        self.phase_plot = (length > 0).then(|| PhasePlot::default().length(length).wrap(wrap));
+    }
+
+    /// Sets a Poincaré section of a double pendulum at a fixed energy,
+    /// drawn as an inset in the bottom-left corner of the canvas.
+    ///
+    /// Records `(theta_2, omega_2)` whenever the first rod swings through
+    /// straight down counterclockwise. Each launch then starts on the
+    /// section at this energy, with `omega_1` solved for from `theta_2` and
+    /// `omega_2` of the initial state, and adds another orbit to the
+    /// section, so restarting from different initial states fills it in.
+    /// Changing the lengths or masses starts a new section. Dragging a
+    /// bob off the section's energy ends its orbit.
+    ///
+    /// # Arguments
+    /// * `energy` - Total energy in joules, zero at the pivot height,
+    ///   or `None` for no section.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the simulation is not a
+    /// frictionless double pendulum.
+    pub fn set_poincare(&mut self, energy: Option<f64>) -> Result<(), JsValue> {
+        if self.kind != Kind::Double || self.lengths.len() != 2 || self.masses.len() != 2 {
+            return Err("Poincaré sections require a double pendulum!".into());
+        }
+        let (params, _) = self.double_pendulum();
+        if energy.is_some() && !is_frictionless(&params) {
+            return Err("Poincaré sections require a frictionless pendulum!".into());
+        }
+        self.poincare =
+            energy.map(|energy| Rc::new(RefCell::new(PoincareSection::new(params, energy))));
+        Ok(())
+    }
+
+    /// Sets the size of an ensemble and how far apart its members start.
+    ///
+    /// # Arguments
+    /// * `members` - Number of double pendulums.
+    /// * `offset` - Difference in radians between the initial angles
+    ///   of both rods of consecutive members.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if there are no members
+    /// or the offset is not finite.
+    pub fn set_ensemble(&mut self, members: usize, offset: f64) -> Result<(), JsValue> {
+        if members == 0 {
+            return Err("An ensemble needs at least one member!".into());
+        }
+        if !offset.is_finite() {
+            return Err("Offset must be finite!".into());
+        }
+        self.members = members;
+        self.offset = offset;
+        Ok(())
+    }
+
+    /// Starts the simulation from its initial conditions,
+    /// or resumes it if it is already running.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the configuration is inconsistent
+    /// or the animation loop cannot be started.
+    pub fn start(&mut self) -> Result<(), JsValue> {
+        if let Some(running) = &self.running {
+            return running.handle.resume();
+        }
+        self.update_poincare();
+        self.running = Some(self.launch()?);
+        Ok(())
+    }
+
+    /// Pauses the simulation.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the animation loop cannot be paused.
+    pub fn pause(&self) -> Result<(), JsValue> {
+        match &self.running {
+            Some(running) => running.handle.pause(),
+            None => Ok(()),
+        }
+    }
+
+    /// Advances a paused simulation by a single frame.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the frame cannot be requested.
+    pub fn step(&self) -> Result<(), JsValue> {
+        match &self.running {
+            Some(running) => running.handle.step(),
+            None => Ok(()),
+        }
+    }
+
+    /// Restarts the simulation from its initial conditions with the
+    /// current settings, staying paused if it was paused.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the configuration is inconsistent
+    /// or the animation loop cannot be restarted.
+    pub fn reset(&mut self) -> Result<(), JsValue> {
+        let paused = self.is_paused();
+        self.stop()?;
+        self.start()?;
+        if paused {
+            self.pause()?;
+        }
+        Ok(())
+    }
+
+    /// Stops the simulation, leaving the last frame on the canvas.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the animation loop cannot be cancelled.
+    pub fn stop(&mut self) -> Result<(), JsValue> {
+        match self.running.take() {
+            Some(running) => running.handle.cancel(),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns whether the simulation has been started and not stopped.
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Returns whether the simulation is paused.
+    pub fn is_paused(&self) -> bool {
+        self.running
+            .as_ref()
+            .is_some_and(|running| running.handle.is_paused())
+    }
+
+    /// Returns the simulated time in seconds.
+    pub fn time(&self) -> f64 {
+        self.read(|sample| sample.t).unwrap_or(0.0)
+    }
+
+    /// Returns the current state as `(theta_1, omega_1, ..., theta_n, omega_n)`,
+    /// or the initial state if not running.
+    pub fn state(&self) -> Vec<f64> {
+        self.read(|sample| {
+            sample
+                .joints
+                .iter()
+                .flat_map(|joint| [joint.theta, joint.omega])
+                .collect()
+        })
+        .unwrap_or_else(|| self.initial.clone())
+    }
+
+    /// Returns the current bob positions in meters relative to
+    /// the pivot as `(x_1, y_1, ..., x_n, y_n)`, with `y` down.
+    pub fn positions(&self) -> Vec<f64> {
+        self.read(|sample| {
+            sample
+                .joints
+                .iter()
+                .flat_map(|joint| [joint.x, joint.y])
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+
+    /// Returns the current total energy in joules.
+    pub fn energy(&self) -> f64 {
+        self.read(|sample| sample.energy).unwrap_or(f64::NAN)
+    }
+
+    /// Returns the running estimate of the maximal Lyapunov exponent
+    /// in 1/s, if enabled with `set_lyapunov` and settled.
+    pub fn lyapunov_exponent(&self) -> Option<f64> {
+        self.running
+            .as_ref()
+            .and_then(|running| running.exponent.get())
+    }
+
+    /// Exports the recorded trajectory as a Blob
+    /// that can be offered as a download.
+    ///
+    /// # Arguments
+    /// * `format` - Either `csv` or `jsonl`.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the format is unknown, the
+    /// simulation was never started or the Blob cannot be created.
+    pub fn export_trajectory(&self, format: &str) -> Result<Blob, JsValue> {
+        let format: Format = format.parse().map_err(|e: String| JsValue::from(e))?;
+        let running = self
+            .running
+            .as_ref()
+            .ok_or(JsValue::from("Simulation is not running!"))?;
+        running.recorder.borrow().to_blob(format)
+    }
+
+    /// Exports the crossings of the Poincaré section as a Blob
+    /// that can be offered as a download.
+    ///
+    /// # Arguments
+    /// * `format` - Either `csv` or `jsonl`.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the format is unknown, no section
+    /// was set or the Blob cannot be created.
+    pub fn export_poincare(&self, format: &str) -> Result<Blob, JsValue> {
+        let format: Format = format.parse().map_err(|e: String| JsValue::from(e))?;
+        let poincare = self
+            .poincare
+            .as_ref()
+            .ok_or(JsValue::from("No Poincaré section!"))?;
+        poincare.borrow().to_blob(format)
+    }
+}
+
+impl PendulumApp {
+    /// Reads the latest sample of the running simulation.
+    fn read<T>(&self, f: impl FnOnce(&Sample) -> T) -> Option<T> {
+        self.running
+            .as_ref()
+            .map(|running| f(&running.sample.borrow()))
+    }
+
+    /// Builds the configured simulation and starts running it.
+    fn launch(&self) -> Result<Running, JsValue> {
+        // Check configuration
+        let n = self.lengths.len();
+        let expected = match self.kind {
+            Kind::Single => 1,
+            Kind::Double | Kind::Ensemble => 2,
+            Kind::Chain => n.max(1),
+        };
+        if n != expected || self.masses.len() != n || self.initial.len() != 2 * n {
+            return Err(JsValue::from(format!(
+                "expected {expected} lengths and masses and {} state values",
+                2 * expected
+            )));
+        }
+        let integrator = integrator::by_name(&self.integrator, TOLERANCE, TOLERANCE)
+            .ok_or(JsValue::from("Unknown integrator!"))?;
+        let friction = |i: usize| self.frictions.get(i).copied().unwrap_or_default();
+
+        // Build simulation
+        match self.kind {
+            Kind::Single => {
+                let params = PendulumParams {
+                    length: self.lengths[0],
+                    mass: self.masses[0],
+                    friction: friction(0),
+                };
+                let state = [self.initial[0], self.initial[1]];
+                self.run(
+                    Pendulum::new(params, state)
+                        .integrator(integrator)
+                        .throw(self.throw)
+                        .trail(self.trail.clone()),
+                )
+            }
+            Kind::Double => {
+                let (params, mut state) = self.double_pendulum();
+                if let Some(poincare) = &self.poincare {
+                    if !is_frictionless(&params) {
+                        return Err("Poincaré sections require a frictionless pendulum!".into());
+                    }
+                    let mut poincare = poincare.borrow_mut();
+                    state = poincare
+                        .initial_state(state[2], state[3])
+                        .ok_or(JsValue::from(format!(
+                            "energy {} J is out of reach!",
+                            poincare.energy()
+                        )))?;
+                    poincare.next_orbit();
+                }
+                let sim = DoublePendulum::new(params, state)
+                    .integrator(integrator)
+                    .throw(self.throw)
+                    .trail(self.trail.clone());
+                if self.lyapunov {
+                    // Shadow with the same integrator, so only chaos separates them
+                    let shadow = integrator::by_name(&self.integrator, TOLERANCE, TOLERANCE)
+                        .ok_or(JsValue::from("Unknown integrator!"))?;
+                    self.run(sim.lyapunov(Lyapunov::default().integrator(shadow)))
+                } else {
+                    self.run(sim)
+                }
+            }
+            Kind::Ensemble => {
+                let (params, state) = self.double_pendulum();
+                let offset = [self.offset, 0.0, self.offset, 0.0];
+                self.run(
+                    Ensemble::new(params, state, self.members, offset).integrator(|| {
+                        integrator::by_name(&self.integrator, TOLERANCE, TOLERANCE).unwrap()
+                    }),
+                )
+            }
+            Kind::Chain => {
+                let links = (0..n)
+                    .map(|i| Link {
+                        length: self.lengths[i],
+                        mass: self.masses[i],
+                        friction: friction(i),
+                    })
+                    .collect();
+                let params = ChainPendulumParams { links };
+                self.run(
+                    ChainPendulum::new(params, self.initial.clone())
+                        .integrator(integrator)
+                        .throw(self.throw)
+                        .trail(self.trail.clone()),
+                )
+            }
+        }
+    }
+
+    /// Starts a new Poincaré section at the same energy if the
+    /// pendulum changed since the section was set.
+    fn update_poincare(&mut self) {
+        if self.kind != Kind::Double || self.lengths.len() != 2 || self.masses.len() != 2 {
+            return;
+        }
+        let (params, _) = self.double_pendulum();
+        if let Some(poincare) = &mut self.poincare
+            && *poincare.borrow().params() != params
+        {
+            let energy = poincare.borrow().energy();
+            *poincare = Rc::new(RefCell::new(PoincareSection::new(params, energy)));
+        }
+    }
+
+    /// Returns the configured double pendulum parameters and initial state.
+    fn double_pendulum(&self) -> (DoublePendulumParams, State) {
+        let friction = |i: usize| self.frictions.get(i).copied().unwrap_or_default();
+        let params = DoublePendulumParams {
+            length_1: self.lengths[0],
+            length_2: self.lengths[1],
+            mass_1: self.masses[0],
+            mass_2: self.masses[1],
+            friction_1: friction(0),
+            friction_2: friction(1),
+        };
+        let state = [
+            self.initial[0],
+            self.initial[1],
+            self.initial[2],
+            self.initial[3],
+        ];
+        (params, state)
+    }
+
+    /// Runs the simulation on the app's canvas, keeping
+    /// its latest sample and trajectory for reading back.
+    fn run<S: Simulation + Observe + Diagnostics + 'static>(
+        &self,
+        sim: S,
+    ) -> Result<Running, JsValue> {
+        let sample = Rc::new(RefCell::new(Sample::of(0.0, &sim)));
+        let recorder = Rc::new(RefCell::new(Recorder::new(RECORDER_CAPACITY)));
+        let window = WindowCtx::new(self.canvas.clone(), self.ctx.clone());
+        let exponent = Rc::new(Cell::new(None));
+        let (latest, latest_exponent) = (Rc::clone(&sample), Rc::clone(&exponent));
+        let mut runner = SimulationRunner::new(sim, window)
+            .camera(Camera::default().auto_fit(self.auto_fit))
+            .step(self.step)?
+            .hud(self.hud)
+            .record(Rc::clone(&recorder))
+            .on_step(move |sim, t| {
+                *latest.borrow_mut() = Sample::of(t, sim);
+                latest_exponent.set(sim.lyapunov_exponent());
+            });
+        if let Some(phase_plot) = &self.phase_plot {
+            runner = runner.phase_plot(phase_plot.clone());
+        }
+        if let (Kind::Double, Some(poincare)) = (self.kind, &self.poincare) {
+            runner = runner.poincare(Rc::clone(poincare));
+        }
+        let handle = runner.run()?;
+        Ok(Running {
+            handle,
+            sample,
+            exponent,
+            recorder,
+        })
+    }
+}
+
+/// Returns whether neither joint of the double pendulum has friction,
+/// so its energy is conserved.
+fn is_frictionless(params: &DoublePendulumParams) -> bool {
+    params.friction_1 == Friction::default() && params.friction_2 == Friction::default()
+}
+
+impl Drop for PendulumApp {
+    /// Stops the animation loop, which would otherwise keep running.
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
@@ -0,0 +1,270 @@
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    process::ExitCode,
+    str::FromStr,
+};
+
+use rust_wasm_double_pendulum::{
+    diagnostics::Diagnostics,
+    double_pendulum::{DoublePendulum, DoublePendulumParams},
+    friction::Friction,
+    integrator::{self, StepStats},
+    pendulum::{Pendulum, PendulumParams},
+    record::{Format, Observe, Sample},
+    sim::{FrameCtx, Simulation, UpdateCtx},
+};
+
+/// Usage text printed on `--help` or invalid arguments.
+const USAGE: &str = "\
+Runs a pendulum simulation headlessly and writes its trajectory as CSV or JSON Lines.
+
+Usage: pendulum-sim [OPTIONS]
+
+Options:
+  --sim <double|single>        Simulation to run [default: double]
+  --integrator <NAME>          euler, semi-implicit-euler, velocity-verlet,
+                               rk4, rk45 or rk45-adaptive [default: rk4]
+  --atol <TOL>                 Absolute tolerance for rk45-adaptive [default: 1e-9]
+  --rtol <TOL>                 Relative tolerance for rk45-adaptive [default: 1e-9]
+  --dt <SECONDS>               Time step [default: 0.004166666666666667]
+  --duration <SECONDS>         Simulated time [default: 10]
+  --length-N <METERS>          Length of rod N [default: 1]
+  --mass-N <KG>                Mass of bob N [default: 1]
+  --theta-N <RADIANS>          Initial angle of rod N [default: 0]
+  --omega-N <RAD/S>            Initial angular velocity of rod N [default: 0]
+  --viscous-N <COEFF>          Viscous damping of joint N [default: 0]
+  --coulomb-N <COEFF>          Coulomb friction of joint N [default: 0]
+  --drag-N <COEFF>             Air drag on rod N [default: 0]
+  --format <csv|jsonl>         Output format [default: csv]
+  --output <FILE>              Write to FILE instead of stdout
+  --help                       Print this help
+
+N is 1 or 2. The single pendulum only uses N = 1.
+";
+
+/// Option names accepted on the command line, without the leading `--`.
+const OPTIONS: &[&str] = &[
+    "sim",
+    "integrator",
+    "atol",
+    "rtol",
+    "dt",
+    "duration",
+    "length-1",
+    "length-2",
+    "mass-1",
+    "mass-2",
+    "theta-1",
+    "theta-2",
+    "omega-1",
+    "omega-2",
+    "viscous-1",
+    "viscous-2",
+    "coulomb-1",
+    "coulomb-2",
+    "drag-1",
+    "drag-2",
+    "format",
+    "output",
+    "help",
+];
+
+/// Parsed `--key value` command line options.
+struct Args(HashMap<String, String>);
+
+impl Args {
+    /// Parses `--key value` pairs from the process arguments.
+    fn parse() -> Result<Self, String> {
+        let mut options = HashMap::new();
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let key = arg
+                .strip_prefix("--")
+                .filter(|key| OPTIONS.contains(key))
+                .ok_or(format!("unexpected argument '{arg}'"))?;
+            if key == "help" {
+                options.insert(key.to_string(), String::new());
+                continue;
+            }
+            let value = args.next().ok_or(format!("missing value for '{arg}'"))?;
+            options.insert(key.to_string(), value);
+        }
+        Ok(Self(options))
+    }
+
+    /// Returns whether the option was given at all.
+    fn has(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    /// Returns the option parsed as `T`, or `default` if not given.
+    fn get<T: FromStr>(&self, key: &str, default: T) -> Result<T, String> {
+        match self.0.get(key) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("invalid value '{value}' for '--{key}'")),
+            None => Ok(default),
+        }
+    }
+
+    /// Returns the option parsed as a positive, finite number,
+    /// or `default` if not given.
+    fn positive(&self, key: &str, default: f64) -> Result<f64, String> {
+        let value = self.get(key, default)?;
+        if !(value > 0.0 && value.is_finite()) {
+            return Err(format!("'--{key}' must be positive and finite"));
+        }
+        Ok(value)
+    }
+
+    /// Returns the option parsed as a non-negative, finite number,
+    /// or `default` if not given.
+    fn non_negative(&self, key: &str, default: f64) -> Result<f64, String> {
+        let value = self.get(key, default)?;
+        if !(value >= 0.0 && value.is_finite()) {
+            return Err(format!("'--{key}' must be non-negative and finite"));
+        }
+        Ok(value)
+    }
+
+    /// Returns the friction of joint `n`.
+    fn friction(&self, n: u8) -> Result<Friction, String> {
+        Ok(Friction {
+            viscous: self.non_negative(&format!("viscous-{n}"), 0.0)?,
+            coulomb: self.non_negative(&format!("coulomb-{n}"), 0.0)?,
+            drag: self.non_negative(&format!("drag-{n}"), 0.0)?,
+        })
+    }
+}
+
+/// A configured simulation of either kind.
+enum Sim {
+    Double(DoublePendulum),
+    Single(Pendulum),
+}
+
+/// Advances the simulation by the given number of steps, writing
+/// one sample of its trajectory per step.
+fn simulate<S: Simulation + Observe + Diagnostics>(
+    sim: &mut S,
+    format: Format,
+    dt: f64,
+    steps: u32,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    if format == Format::Csv {
+        writeln!(out, "{}", Sample::csv_header(sim.joints().len()))?;
+    }
+    for frame in 0..=steps {
+        // Write sample
+        let ts = frame as f64 * dt;
+        let sample = Sample::of(ts, sim);
+        match format {
+            Format::Csv => writeln!(out, "{}", sample.to_csv())?,
+            Format::JsonLines => writeln!(out, "{}", sample.to_json())?,
+        }
+
+        // Update sim
+        if frame < steps {
+            let frame = FrameCtx { frame, dt, ts };
+            let update = UpdateCtx {
+                frame: &frame,
+                dt,
+                steps: Cell::new(StepStats::default()),
+            };
+            sim.update(&update);
+        }
+    }
+    out.flush()
+}
+
+/// Builds the configured simulation and writes its trajectory.
+fn run(args: &Args) -> Result<(), String> {
+    // Integration settings
+    let name: String = args.get("integrator", "rk4".to_string())?;
+    let atol = args.positive("atol", 1e-9)?;
+    let rtol = args.positive("rtol", 1e-9)?;
+    let integrator =
+        integrator::by_name(&name, atol, rtol).ok_or(format!("unknown integrator '{name}'"))?;
+    let dt = args.positive("dt", 1.0 / 240.0)?;
+    let duration = args.non_negative("duration", 10.0)?;
+    let steps = (duration / dt).round();
+    if steps > u32::MAX as f64 {
+        return Err(format!(
+            "'--duration' needs {steps} steps, at most {} are supported",
+            u32::MAX
+        ));
+    }
+    let steps = steps as u32;
+    let format: String = args.get("format", "csv".to_string())?;
+    let format: Format = format.parse()?;
+
+    // Simulation
+    let sim: String = args.get("sim", "double".to_string())?;
+    let mut sim = match sim.as_str() {
+        "double" => {
+            let params = DoublePendulumParams {
+                length_1: args.positive("length-1", 1.0)?,
+                length_2: args.positive("length-2", 1.0)?,
+                mass_1: args.positive("mass-1", 1.0)?,
+                mass_2: args.positive("mass-2", 1.0)?,
+                friction_1: args.friction(1)?,
+                friction_2: args.friction(2)?,
+            };
+            let state = [
+                args.get("theta-1", 0.0)?,
+                args.get("omega-1", 0.0)?,
+                args.get("theta-2", 0.0)?,
+                args.get("omega-2", 0.0)?,
+            ];
+            Sim::Double(DoublePendulum::new(params, state).integrator(integrator))
+        }
+        "single" => {
+            let params = PendulumParams {
+                length: args.positive("length-1", 1.0)?,
+                mass: args.positive("mass-1", 1.0)?,
+                friction: args.friction(1)?,
+            };
+            let state = [args.get("theta-1", 0.0)?, args.get("omega-1", 0.0)?];
+            Sim::Single(Pendulum::new(params, state).integrator(integrator))
+        }
+        _ => return Err(format!("unknown simulation '{sim}'")),
+    };
+
+    // Output, opened only once every option is valid
+    let mut out: Box<dyn Write> = match args.0.get("output") {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).map_err(|e| format!("cannot create '{path}': {e}"))?,
+        )),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    let result = match &mut sim {
+        Sim::Double(sim) => simulate(sim, format, dt, steps, &mut out),
+        Sim::Single(sim) => simulate(sim, format, dt, steps, &mut out),
+    };
+    result.map_err(|e| format!("failed to write trajectory: {e}"))
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    if args.has("help") {
+        print!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
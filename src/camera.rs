@@ -0,0 +1,166 @@
+use crate::{
+    consts::METERS_TO_PIXELS,
+    input::{PointerEvent, PointerPhase, WheelEvent},
+};
+
+/// Fraction of the canvas left empty around the fitted region on each side.
+const FIT_MARGIN: f64 = 0.05;
+
+/// Zoom factor per pixel of mouse wheel scrolling.
+const WHEEL_ZOOM: f64 = 0.001;
+
+/// Axis-aligned region of world space, in meters.
+///
+/// # Fields
+/// * `min` - The corner with the smallest coordinates.
+/// * `max` - The corner with the largest coordinates.
+#[derive(Clone, Copy, Debug)]
+pub struct Bounds {
+    pub min: (f64, f64),
+    pub max: (f64, f64),
+}
+
+impl Bounds {
+    /// Square region reaching `radius` meters from the pivot in every direction.
+    pub fn around_pivot(radius: f64) -> Self {
+        Self {
+            min: (-radius, -radius),
+            max: (radius, radius),
+        }
+    }
+}
+
+/// Maps world space, in meters with the pivot at the origin and
+/// `y` pointing down, to screen space in CSS pixels.
+///
+/// Shows the `target` world point at the `anchor` point of the canvas,
+/// given as a fraction of the canvas size, magnified by `scale`.
+/// Can be panned by dragging and zoomed with the mouse wheel, and
+/// can keep a region of world space fitted to the canvas.
+pub struct Camera {
+    pub target: (f64, f64),
+    pub anchor: (f64, f64),
+    /// Pixels per meter.
+    pub scale: f64,
+    auto_fit: bool,
+    viewport: (f64, f64),
+    pan: Option<(i32, f64, f64)>,
+}
+
+impl Default for Camera {
+    /// Shows the pivot centered a quarter of the way down the canvas,
+    /// at `METERS_TO_PIXELS`, auto-fitting the simulation.
+    fn default() -> Self {
+        Self {
+            target: (0.0, 0.0),
+            anchor: (0.5, 0.25),
+            scale: METERS_TO_PIXELS,
+            auto_fit: true,
+            viewport: (0.0, 0.0),
+            pan: None,
+        }
+    }
+}
+
+impl Camera {
+    /// Sets whether the camera keeps the simulation fitted to the canvas.
+    ///
+    /// Panning or zooming by hand turns auto-fit off.
+    pub fn auto_fit(self, auto_fit: bool) -> Self {
+        Self { auto_fit, ..self }
+    }
+
+    /// Returns whether the camera keeps the simulation fitted to the canvas.
+    pub fn is_auto_fit(&self) -> bool {
+        self.auto_fit
+    }
+
+    /// Sets the size of the canvas in CSS pixels.
+    pub fn set_viewport(&mut self, width: f64, height: f64) {
+        self.viewport = (width, height);
+    }
+
+    /// Converts a point from world space to screen space.
+    pub fn to_screen(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            self.anchor.0 * self.viewport.0 + (x - self.target.0) * self.scale,
+            self.anchor.1 * self.viewport.1 + (y - self.target.1) * self.scale,
+        )
+    }
+
+    /// Converts a point from screen space to world space.
+    pub fn to_world(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            self.target.0 + (x - self.anchor.0 * self.viewport.0) / self.scale,
+            self.target.1 + (y - self.anchor.1 * self.viewport.1) / self.scale,
+        )
+    }
+
+    /// Converts a length from meters to pixels.
+    pub fn to_pixels(&self, meters: f64) -> f64 {
+        meters * self.scale
+    }
+
+    /// Moves the view by the given distance in pixels.
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.target.0 -= dx / self.scale;
+        self.target.1 -= dy / self.scale;
+    }
+
+    /// Magnifies the view by `factor`, keeping the world
+    /// point under the given screen point in place.
+    pub fn zoom_at(&mut self, factor: f64, screen: (f64, f64)) {
+        let world = self.to_world(screen);
+        self.scale *= factor;
+        let moved = self.to_screen(world);
+        self.pan(screen.0 - moved.0, screen.1 - moved.1);
+    }
+
+    /// Centers the view on a region of world space, as large
+    /// as fits the canvas with a small margin.
+    pub fn fit(&mut self, bounds: Bounds) {
+        let (width, height) = (bounds.max.0 - bounds.min.0, bounds.max.1 - bounds.min.1);
+        let usable = 1.0 - 2.0 * FIT_MARGIN;
+        let scale = (self.viewport.0 * usable / width).min(self.viewport.1 * usable / height);
+        if scale.is_finite() && scale > 0.0 {
+            self.scale = scale;
+        }
+        self.target = (
+            (bounds.min.0 + bounds.max.0) / 2.0,
+            (bounds.min.1 + bounds.max.1) / 2.0,
+        );
+        self.anchor = (0.5, 0.5);
+    }
+
+    /// Pans with pointer drags and zooms with the mouse wheel.
+    ///
+    /// Panning or zooming turns auto-fit off.
+    ///
+    /// # Arguments
+    /// * `pointer` - Pointer events not claimed by the simulation.
+    /// * `wheel` - Mouse wheel events.
+    pub fn handle(&mut self, pointer: &[PointerEvent], wheel: &[WheelEvent]) {
+        for event in pointer {
+            match (event.phase, self.pan) {
+                (PointerPhase::Down, None) => self.pan = Some((event.id, event.x, event.y)),
+                (PointerPhase::Move, Some((id, x, y))) if id == event.id => {
+                    self.auto_fit = false;
+                    self.pan(event.x - x, event.y - y);
+                    self.pan = Some((id, event.x, event.y));
+                }
+                (PointerPhase::Up, Some((id, _, _))) if id == event.id => self.pan = None,
+                _ => {}
+            }
+        }
+        for event in wheel {
+            self.auto_fit = false;
+            self.zoom_at((-event.delta * WHEEL_ZOOM).exp(), (event.x, event.y));
+        }
+    }
+
+    /// Stops any pan in progress, e.g. when the simulation
+    /// claims the pointer to drag a bob.
+    pub fn cancel_pan(&mut self) {
+        self.pan = None;
+    }
+}
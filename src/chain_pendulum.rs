@@ -0,0 +1,439 @@
+#[cfg(feature = "web")]
+use crate::{
+    camera::Bounds,
+    color::Color,
+    consts::ROD_WIDTH,
+    draw::{FilledCircle, Line, LineCap, Style},
+    sim::{InputCtx, RenderCtx},
+};
+use crate::{
+    consts::GRAVITY,
+    diagnostics::Diagnostics,
+    friction::Friction,
+    input::Dragger,
+    integrator::{Dynamics, Integrator, Rk4},
+    record::{Joint, Observe},
+    sim::{Simulation, UpdateCtx},
+    trail::Trail,
+};
+
+/// Radius of a bob in meters.
+#[cfg(feature = "web")]
+const BOB_RADIUS: f64 = 0.05;
+
+/// Radius of the pivot in meters.
+#[cfg(feature = "web")]
+const PIVOT_RADIUS: f64 = 0.025;
+
+/// Colors cycled through for the rods, from the pivot outward.
+#[cfg(feature = "web")]
+const ROD_COLORS: [Color; 4] = [
+    Color::hex(0xffff00),
+    Color::hex(0xff00ff),
+    Color::hex(0x00ffff),
+    Color::hex(0xff8800),
+];
+
+/// Colors cycled through for the bobs, from the pivot outward.
+#[cfg(feature = "web")]
+const BOB_COLORS: [Color; 4] = [
+    Color::hex(0x00ff00),
+    Color::hex(0x00aaff),
+    Color::hex(0xff4444),
+    Color::hex(0xaa88ff),
+];
+
+/// A single link of a chain pendulum: a massless rod
+/// ending in a point-mass bob, attached by a joint.
+#[derive(Clone, Copy)]
+pub struct Link {
+    pub length: f64,
+    pub mass: f64,
+    pub friction: Friction,
+}
+
+impl Default for Link {
+    /// A frictionless 1m link with a 1kg bob.
+    fn default() -> Self {
+        Self {
+            length: 1.0,
+            mass: 1.0,
+            friction: Friction::default(),
+        }
+    }
+}
+
+/// Physical parameters of a chain pendulum.
+///
+/// Supplies the equations of motion to the integrator
+/// through its `Dynamics` implementation.
+#[derive(Clone)]
+pub struct ChainPendulumParams {
+    pub links: Vec<Link>,
+}
+
+impl ChainPendulumParams {
+    /// Computes the mass carried by each link, i.e. the
+    /// total mass from that link to the end of the chain.
+    fn carried_masses(&self) -> Vec<f64> {
+        let mut mu = vec![0.0; self.links.len()];
+        for i in (0..self.links.len()).rev() {
+            mu[i] = self.links[i].mass + mu.get(i + 1).copied().unwrap_or(0.0);
+        }
+        mu
+    }
+
+    /// Kinetic energy of all bobs in the given state.
+    pub fn kinetic_energy(&self, state: &[f64]) -> f64 {
+        let mu = self.carried_masses();
+        let mut energy = 0.0;
+        for (i, link_i) in self.links.iter().enumerate() {
+            for (j, link_j) in self.links.iter().enumerate() {
+                let cos_diff = (state[2 * i] - state[2 * j]).cos();
+                energy += 0.5
+                    * link_i.length
+                    * link_j.length
+                    * state[2 * i + 1]
+                    * state[2 * j + 1]
+                    * cos_diff
+                    * mu[i.max(j)];
+            }
+        }
+        energy
+    }
+
+    /// Potential energy of all bobs in the given state,
+    /// zero at the pivot height.
+    pub fn potential_energy(&self, state: &[f64]) -> f64 {
+        let mu = self.carried_masses();
+        self.links
+            .iter()
+            .enumerate()
+            .map(|(i, link)| -mu[i] * GRAVITY * link.length * state[2 * i].cos())
+            .sum()
+    }
+
+    /// Angular momentum of all bobs about the pivot in the given state.
+    pub fn angular_momentum(&self, state: &[f64]) -> f64 {
+        let mu = self.carried_masses();
+        let mut momentum = 0.0;
+        for (i, link_i) in self.links.iter().enumerate() {
+            for (j, link_j) in self.links.iter().enumerate() {
+                let cos_diff = (state[2 * i] - state[2 * j]).cos();
+                momentum +=
+                    link_i.length * link_j.length * state[2 * j + 1] * cos_diff * mu[i.max(j)];
+            }
+        }
+        momentum
+    }
+}
+
+impl Dynamics for ChainPendulumParams {
+    /// Computes the time derivative of the given state.
+    ///
+    /// Builds the mass matrix and generalized forces of the chain and
+    /// solves `M * alpha = f` for the angular accelerations:
+    ///
+    /// M_ij = L_i L_j cos(theta_i - theta_j) mu_max(i, j)
+    /// f_i = -sum_j L_i L_j sin(theta_i - theta_j) omega_j^2 mu_max(i, j)
+    ///       - g L_i sin(theta_i) mu_i + (friction torques)
+    ///
+    /// where `mu_i` is the total mass from link `i` to the end of the chain.
+    fn derivative(&self, state: &[f64], out: &mut [f64]) {
+        let n = self.links.len();
+        assert_eq!(
+            state.len(),
+            2 * n,
+            "chain state must have 2 components per link"
+        );
+        let theta = |i: usize| state[2 * i];
+        let omega = |i: usize| state[2 * i + 1];
+
+        // Mass carried by each link and all links below it
+        let mu = self.carried_masses();
+
+        // Friction torques. Each joint acts on its own link and,
+        // oppositely, on the link above it. Drag acts on each link's own rate.
+        let mut torque = vec![0.0; n];
+        for (i, link) in self.links.iter().enumerate() {
+            let omega_above = if i == 0 { 0.0 } else { omega(i - 1) };
+            let joint = link.friction.joint_torque(omega(i) - omega_above);
+            torque[i] += joint + link.friction.drag_torque(omega(i));
+            if i > 0 {
+                torque[i - 1] -= joint;
+            }
+        }
+
+        // Mass matrix and generalized forces
+        let mut mass = vec![vec![0.0; n]; n];
+        let mut force = vec![0.0; n];
+        for i in 0..n {
+            let l_i = self.links[i].length;
+            force[i] = -GRAVITY * l_i * theta(i).sin() * mu[i] + torque[i];
+            for j in 0..n {
+                let l_j = self.links[j].length;
+                let (sin_diff, cos_diff) = (theta(i) - theta(j)).sin_cos();
+                let mu_ij = mu[i.max(j)];
+                mass[i][j] = l_i * l_j * cos_diff * mu_ij;
+                force[i] -= l_i * l_j * sin_diff * omega(j) * omega(j) * mu_ij;
+            }
+        }
+
+        // Angular accelerations
+        let alpha = solve(mass, force);
+        for i in 0..n {
+            out[2 * i] = omega(i);
+            out[2 * i + 1] = alpha[i];
+        }
+    }
+}
+
+/// Solves the linear system `a * x = b` by Gaussian
+/// elimination with partial pivoting.
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Vec<f64> {
+    let n = b.len();
+
+    // Forward elimination
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap();
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..n {
+            let factor = a[row][col] / a[col][col];
+            let (above, below) = a.split_at_mut(row);
+            for (x, p) in below[0][col..].iter_mut().zip(&above[col][col..]) {
+                *x -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    // Back substitution
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    x
+}
+
+/// Represents a pendulum made of an arbitrary number of links.
+///
+/// Each link has its own length, mass and joint friction, and the chain
+/// tracks the absolute angle (theta) and angular velocity (omega) of every link.
+pub struct ChainPendulum {
+    pub params: ChainPendulumParams,
+    state: Vec<f64>,
+    prev: Vec<f64>,
+    integrator: Box<dyn Integrator>,
+    initial_energy: f64,
+    drag: Dragger,
+    trail: Option<Trail>,
+}
+
+impl ChainPendulum {
+    /// Creates a new ChainPendulum with the specified physical
+    /// parameters and initial state.
+    ///
+    /// Integrates with `Rk4` unless configured otherwise.
+    ///
+    /// # Arguments
+    /// * `params` - The links, from the pivot outward.
+    /// * `state` - Initial `(theta_1, omega_1, ..., theta_n, omega_n)`.
+    pub fn new(params: ChainPendulumParams, state: Vec<f64>) -> Self {
+        assert_eq!(
+            state.len(),
+            2 * params.links.len(),
+            "chain state must have 2 components per link"
+        );
+        let initial_energy = params.kinetic_energy(&state) + params.potential_energy(&state);
+        Self {
+            params,
+            prev: state.clone(),
+            state,
+            integrator: Box::new(Rk4),
+            initial_energy,
+            drag: Dragger::default(),
+            trail: None,
+        }
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> &[f64] {
+        &self.state
+    }
+
+    /// Sets the integrator used to advance the simulation.
+    pub fn integrator(self, integrator: impl Integrator + 'static) -> Self {
+        Self {
+            integrator: Box::new(integrator),
+            ..self
+        }
+    }
+
+    /// Sets whether a dragged bob keeps its velocity when released.
+    pub fn throw(self, throw: bool) -> Self {
+        Self {
+            drag: Dragger::new(throw),
+            ..self
+        }
+    }
+
+    /// Sets a trail to draw behind the bobs.
+    pub fn trail(self, trail: Trail) -> Self {
+        Self {
+            trail: Some(trail),
+            ..self
+        }
+    }
+}
+
+impl Simulation for ChainPendulum {
+    /// Renders the pendulum on the provided render context.
+    ///
+    /// Draws the pivot point, then every rod and bob down the chain.
+    #[cfg(feature = "web")]
+    fn render(&self, render: &RenderCtx) {
+        let state = render.interpolate_slice(&self.prev, &self.state);
+
+        // Nodes, from the pivot outward
+        let mut nodes = vec![(0.0, 0.0)];
+        for (i, link) in self.params.links.iter().enumerate() {
+            let (x, y) = nodes[i];
+            let (s, c) = state[2 * i].sin_cos();
+            nodes.push((x + link.length * s, y + link.length * c));
+        }
+        let nodes: Vec<_> = nodes
+            .into_iter()
+            .map(|node| render.camera.to_screen(node))
+            .collect();
+
+        // Sizes
+        let pivot_radius = render.camera.to_pixels(PIVOT_RADIUS);
+        let bob_radius = render.camera.to_pixels(BOB_RADIUS);
+        let rod_width = render.camera.to_pixels(ROD_WIDTH);
+
+        // Render
+        render.clear();
+        if let Some(trail) = &self.trail {
+            trail.render(render);
+        }
+        for (i, pair) in nodes.windows(2).enumerate() {
+            let [(x_0, y_0), (x_1, y_1)] = [pair[0], pair[1]];
+            let color = ROD_COLORS[i % ROD_COLORS.len()];
+            render.draw(
+                &Line(x_0, y_0, x_1, y_1)
+                    .styled()
+                    .stroke(color.into())
+                    .line_width(rod_width)
+                    .line_cap(LineCap::Round),
+            );
+        }
+        render.draw(
+            &FilledCircle(nodes[0], pivot_radius)
+                .styled()
+                .fill(Color::WHITE.into()),
+        );
+        for (i, &node) in nodes.iter().skip(1).enumerate() {
+            let color = BOB_COLORS[i % BOB_COLORS.len()];
+            render.draw(
+                &FilledCircle(node, bob_radius)
+                    .styled()
+                    .fill(color.into())
+                    .shadow_blur(bob_radius)
+                    .shadow_color(color),
+            );
+        }
+    }
+
+    /// Lets any bob be dragged around with the pointer, the
+    /// links above it following by inverse kinematics.
+    ///
+    /// Energy is measured from the moment the bob is released.
+    #[cfg(feature = "web")]
+    fn input(&mut self, input: &InputCtx) {
+        let lengths: Vec<f64> = self.params.links.iter().map(|link| link.length).collect();
+        for event in input.events {
+            let target = input.to_world(event);
+            if self.drag.handle(event, target, &lengths, &mut self.state) {
+                self.initial_energy = self.total_energy();
+            }
+        }
+        if self.drag.is_dragging() {
+            input.claim();
+        }
+    }
+
+    /// The chain can reach the sum of its link lengths from the pivot.
+    #[cfg(feature = "web")]
+    fn bounds(&self) -> Option<Bounds> {
+        let reach = self.params.links.iter().map(|link| link.length).sum();
+        Some(Bounds::around_pivot(reach))
+    }
+
+    /// Updates the pendulum's state based on the update context.
+    ///
+    /// Advances the state by one fixed step using the configured integrator,
+    /// and reports the integrator's step counts to the context.
+    /// Holds still while a bob is being dragged.
+    fn update(&mut self, update: &UpdateCtx) {
+        self.prev.copy_from_slice(&self.state);
+        if self.drag.is_dragging() {
+            return;
+        }
+        let stats = self
+            .integrator
+            .step(&self.params, &mut self.state, update.dt);
+        update.record(stats);
+        if let Some(mut trail) = self.trail.take() {
+            trail.push(&self.joints());
+            self.trail = Some(trail);
+        }
+    }
+
+    fn diagnostics(&self) -> Option<&dyn Diagnostics> {
+        Some(self)
+    }
+}
+
+impl Diagnostics for ChainPendulum {
+    fn kinetic_energy(&self) -> f64 {
+        self.params.kinetic_energy(&self.state)
+    }
+
+    fn potential_energy(&self) -> f64 {
+        self.params.potential_energy(&self.state)
+    }
+
+    fn initial_energy(&self) -> f64 {
+        self.initial_energy
+    }
+
+    fn angular_momentum(&self) -> f64 {
+        self.params.angular_momentum(&self.state)
+    }
+}
+
+impl Observe for ChainPendulum {
+    fn joints(&self) -> Vec<Joint> {
+        let (mut x, mut y) = (0.0, 0.0);
+        self.params
+            .links
+            .iter()
+            .zip(self.state.chunks(2))
+            .map(|(link, joint)| {
+                let (s, c) = joint[0].sin_cos();
+                x += link.length * s;
+                y += link.length * c;
+                Joint {
+                    theta: joint[0],
+                    omega: joint[1],
+                    x,
+                    y,
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,308 @@
+use std::{fmt, str::FromStr};
+
+/// An sRGB color with alpha.
+///
+/// Channels are stored as fractions between 0 and 1, so colors can be
+/// blended without rounding. Formats as a CSS color string, and parses
+/// CSS hex, `rgb()` and `hsl()` colors.
+///
+/// # Fields
+/// * `r` - Red channel.
+/// * `g` - Green channel.
+/// * `b` - Blue channel.
+/// * `a` - Opacity, from transparent at 0 to opaque at 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Color {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub a: f64,
+}
+
+impl Color {
+    pub const BLACK: Self = Self::hex(0x000000);
+    pub const WHITE: Self = Self::hex(0xffffff);
+    pub const TRANSPARENT: Self = Self::BLACK.alpha(0.0);
+
+    /// Creates an opaque color from a `0xRRGGBB` integer.
+    pub const fn hex(rgb: u32) -> Self {
+        Self::rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+    }
+
+    /// Creates an opaque color from 8-bit channels.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self {
+            r: r as f64 / 255.0,
+            g: g as f64 / 255.0,
+            b: b as f64 / 255.0,
+            a: 1.0,
+        }
+    }
+
+    /// Creates an opaque color from hue, saturation and lightness.
+    ///
+    /// # Arguments
+    /// * `h` - Hue in degrees, wrapped to `[0, 360)`.
+    /// * `s` - Saturation, between 0 and 1.
+    /// * `l` - Lightness, between 0 and 1.
+    pub fn hsl(h: f64, s: f64, l: f64) -> Self {
+        let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+        let channel = |n: f64| {
+            let k = (n + h.rem_euclid(360.0) / 30.0) % 12.0;
+            let a = s * l.min(1.0 - l);
+            l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+        };
+        Self {
+            r: channel(0.0),
+            g: channel(8.0),
+            b: channel(4.0),
+            a: 1.0,
+        }
+    }
+
+    /// Returns the same color with the given opacity.
+    pub const fn alpha(self, a: f64) -> Self {
+        Self { a, ..self }
+    }
+
+    /// Returns the color as hue in degrees, saturation and lightness.
+    pub fn to_hsl(self) -> (f64, f64, f64) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        if d == 0.0 {
+            return (0.0, 0.0, l);
+        }
+        let s = d / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == self.r {
+            (self.g - self.b) / d
+        } else if max == self.g {
+            (self.b - self.r) / d + 2.0
+        } else {
+            (self.r - self.g) / d + 4.0
+        };
+        ((60.0 * h).rem_euclid(360.0), s, l)
+    }
+
+    /// Blends linearly towards another color, channel by channel.
+    ///
+    /// # Arguments
+    /// * `other` - The color reached at `t = 1`.
+    /// * `t` - Blend fraction, clamped between 0 and 1.
+    pub fn lerp(self, other: Self, t: f64) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: f64, b: f64| a + (b - a) * t;
+        Self {
+            r: mix(self.r, other.r),
+            g: mix(self.g, other.g),
+            b: mix(self.b, other.b),
+            a: mix(self.a, other.a),
+        }
+    }
+
+    /// Returns the channels as 8-bit values.
+    fn to_rgb8(self) -> [u8; 3] {
+        [self.r, self.g, self.b].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+}
+
+impl fmt::Display for Color {
+    /// Formats as `#rrggbb` if opaque, or `rgba(r, g, b, a)` otherwise.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [r, g, b] = self.to_rgb8();
+        if self.a >= 1.0 {
+            write!(f, "#{r:02x}{g:02x}{b:02x}")
+        } else {
+            write!(f, "rgba({r}, {g}, {b}, {})", self.a.clamp(0.0, 1.0))
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    /// Parses a CSS color: `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`,
+    /// `rgb()`, `rgba()`, `hsl()` or `hsla()`, with either comma or
+    /// space separated arguments.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid color '{s}'");
+        let css = s.trim().to_ascii_lowercase();
+
+        // Hex
+        if let Some(digits) = css.strip_prefix('#') {
+            let nibbles: Vec<u8> = digits
+                .chars()
+                .map(|c| c.to_digit(16).map(|d| d as u8))
+                .collect::<Option<_>>()
+                .ok_or_else(error)?;
+            let channels: Vec<u8> = match nibbles.len() {
+                3 | 4 => nibbles.iter().map(|n| n * 17).collect(),
+                6 | 8 => nibbles.chunks(2).map(|n| n[0] * 16 + n[1]).collect(),
+                _ => return Err(error()),
+            };
+            let color = Self::rgb(channels[0], channels[1], channels[2]);
+            return Ok(match channels.get(3) {
+                Some(&a) => color.alpha(a as f64 / 255.0),
+                None => color,
+            });
+        }
+
+        // Functional notation
+        let (name, args) = css
+            .strip_suffix(')')
+            .and_then(|css| css.split_once('('))
+            .ok_or_else(error)?;
+        let args: Vec<&str> = args
+            .split([',', '/', ' '])
+            .filter(|arg| !arg.is_empty())
+            .collect();
+        let (channels, alpha) = match args.len() {
+            3 => (&args[..], None),
+            4 => (&args[..3], Some(args[3])),
+            _ => return Err(error()),
+        };
+        let color = match name.trim() {
+            "rgb" | "rgba" => {
+                let [r, g, b] = [0, 1, 2].map(|i| parse_fraction(channels[i], 255.0));
+                Self {
+                    r: r.ok_or_else(error)?,
+                    g: g.ok_or_else(error)?,
+                    b: b.ok_or_else(error)?,
+                    a: 1.0,
+                }
+            }
+            "hsl" | "hsla" => {
+                let h = channels[0].strip_suffix("deg").unwrap_or(channels[0]);
+                let h = h.parse::<f64>().map_err(|_| error())?;
+                let s = parse_fraction(channels[1], 100.0).ok_or_else(error)?;
+                let l = parse_fraction(channels[2], 100.0).ok_or_else(error)?;
+                Self::hsl(h, s, l)
+            }
+            _ => return Err(error()),
+        };
+        match alpha {
+            Some(a) => Ok(color.alpha(parse_fraction(a, 1.0).ok_or_else(error)?)),
+            None => Ok(color),
+        }
+    }
+}
+
+/// Parses a CSS number or percentage as a fraction between 0 and 1.
+///
+/// # Arguments
+/// * `arg` - The number, or the percentage ending in `%`.
+/// * `max` - The value of a plain number corresponding to 1.
+fn parse_fraction(arg: &str, max: f64) -> Option<f64> {
+    let value = match arg.strip_suffix('%') {
+        Some(percent) => percent.parse::<f64>().ok()? / 100.0,
+        None => arg.parse::<f64>().ok()? / max,
+    };
+    value.is_finite().then(|| value.clamp(0.0, 1.0))
+}
+
+/// Range of hues in degrees swept by the rainbow colormap, stopping
+/// short of a full turn so both ends are distinguishable.
+const RAINBOW_HUES: f64 = 300.0;
+
+/// Colormap, mapping values between 0 and 1 to colors.
+///
+/// The perceptually uniform maps are approximated by the colors of the
+/// matplotlib original at evenly spaced points, interpolated linearly
+/// in between.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Colormap {
+    /// Dark blue through green to yellow.
+    Viridis,
+    /// Black through purple and red to light yellow.
+    Magma,
+    /// Cyclic, light grey through blue, dark purple and red back to grey.
+    /// Suited to angles and other periodic values.
+    Twilight,
+    /// Fully saturated hues from red through green and blue to magenta.
+    /// Not perceptually uniform, but tells many overlaid items apart.
+    Rainbow,
+}
+
+impl Colormap {
+    /// Returns the colors of the map at evenly spaced points,
+    /// or `None` for maps computed from a formula.
+    fn stops(self) -> Option<&'static [Color]> {
+        const VIRIDIS: [Color; 11] = [
+            Color::hex(0x440154),
+            Color::hex(0x482475),
+            Color::hex(0x414487),
+            Color::hex(0x355f8d),
+            Color::hex(0x2a788e),
+            Color::hex(0x21918c),
+            Color::hex(0x22a884),
+            Color::hex(0x44bf70),
+            Color::hex(0x7ad151),
+            Color::hex(0xbddf26),
+            Color::hex(0xfde725),
+        ];
+        const MAGMA: [Color; 11] = [
+            Color::hex(0x000004),
+            Color::hex(0x140e36),
+            Color::hex(0x3b0f70),
+            Color::hex(0x641a80),
+            Color::hex(0x8c2981),
+            Color::hex(0xb73779),
+            Color::hex(0xde4968),
+            Color::hex(0xf7705c),
+            Color::hex(0xfe9f6d),
+            Color::hex(0xfecf92),
+            Color::hex(0xfcfdbf),
+        ];
+        const TWILIGHT: [Color; 11] = [
+            Color::hex(0xe2d9e2),
+            Color::hex(0xa9bfd3),
+            Color::hex(0x7a98c8),
+            Color::hex(0x6070bc),
+            Color::hex(0x5a44a2),
+            Color::hex(0x2f1436),
+            Color::hex(0x782c58),
+            Color::hex(0xa9484e),
+            Color::hex(0xc5775c),
+            Color::hex(0xd6aa93),
+            Color::hex(0xe2d9e2),
+        ];
+        match self {
+            Self::Viridis => Some(&VIRIDIS),
+            Self::Magma => Some(&MAGMA),
+            Self::Twilight => Some(&TWILIGHT),
+            Self::Rainbow => None,
+        }
+    }
+
+    /// Returns the color at `t`.
+    ///
+    /// Values outside `[0, 1]` are clamped, or wrapped around for cyclic maps.
+    pub fn sample(self, t: f64) -> Color {
+        let t = match self {
+            Self::Twilight => t.rem_euclid(1.0),
+            _ => t.clamp(0.0, 1.0),
+        };
+        let Some(stops) = self.stops() else {
+            return Color::hsl(RAINBOW_HUES * t, 1.0, 0.5);
+        };
+        let x = t * (stops.len() - 1) as f64;
+        let i = (x.floor() as usize).min(stops.len() - 2);
+        stops[i].lerp(stops[i + 1], x - i as f64)
+    }
+}
+
+impl FromStr for Colormap {
+    type Err = String;
+
+    /// Parses a colormap name: `viridis`, `magma`, `twilight` or `rainbow`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "viridis" => Ok(Self::Viridis),
+            "magma" => Ok(Self::Magma),
+            "twilight" => Ok(Self::Twilight),
+            "rainbow" => Ok(Self::Rainbow),
+            _ => Err(format!("unknown colormap '{s}'")),
+        }
+    }
+}
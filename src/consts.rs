@@ -2,5 +2,9 @@
 pub const GRAVITY: f64 = 9.81;
 
 /// A conversion between meters irl to pixels in screen space
-/// I.e. how many pixels long represents a meter.
+/// I.e. how many pixels long represents a meter,
+/// before the camera is zoomed or fitted.
 pub const METERS_TO_PIXELS: f64 = 200.0;
+
+/// Thickness of the drawn rods, in meters.
+pub const ROD_WIDTH: f64 = 0.012;
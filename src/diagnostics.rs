@@ -0,0 +1,44 @@
+/// Trait for simulations that can report conserved quantities.
+///
+/// Energies are in joules with the potential energy zero at the pivot
+/// height, and angular momentum is about the pivot in kg·m²/s, positive
+/// counterclockwise on screen. Useful for validating integrators, since a
+/// frictionless system should keep its total energy constant.
+pub trait Diagnostics {
+    /// Kinetic energy of all bobs.
+    fn kinetic_energy(&self) -> f64;
+
+    /// Gravitational potential energy of all bobs.
+    fn potential_energy(&self) -> f64;
+
+    /// Total energy at construction, i.e. at t=0.
+    fn initial_energy(&self) -> f64;
+
+    /// Angular momentum of all bobs about the pivot.
+    fn angular_momentum(&self) -> f64;
+
+    /// Total mechanical energy.
+    fn total_energy(&self) -> f64 {
+        self.kinetic_energy() + self.potential_energy()
+    }
+
+    /// Relative drift of the total energy since t=0.
+    ///
+    /// Computed as `(E - E_0) / |E_0|`, or the absolute drift `E - E_0`
+    /// when the initial energy is exactly zero.
+    fn energy_drift(&self) -> f64 {
+        let initial = self.initial_energy();
+        let drift = self.total_energy() - initial;
+        if initial == 0.0 {
+            drift
+        } else {
+            drift / initial.abs()
+        }
+    }
+
+    /// Running estimate of the maximal Lyapunov exponent in 1/s,
+    /// if the simulation tracks one and it has had time to settle.
+    fn lyapunov_exponent(&self) -> Option<f64> {
+        None
+    }
+}
@@ -1,40 +1,374 @@
+#[cfg(feature = "web")]
 use crate::{
-    consts::{GRAVITY, METERS_TO_PIXELS},
-    draw::{FilledCircle, Line, Style},
-    sim::{RenderCtx, Simulation, UpdateCtx},
+    camera::Bounds,
+    color::Color,
+    consts::ROD_WIDTH,
+    draw::{FilledCircle, Line, LineCap, Style},
+    sim::{InputCtx, RenderCtx},
+};
+use crate::{
+    consts::GRAVITY,
+    diagnostics::Diagnostics,
+    friction::Friction,
+    input::Dragger,
+    integrator::{Dynamics, Integrator, Rk4},
+    lyapunov::Lyapunov,
+    record::{Joint, Observe},
+    sim::{Simulation, UpdateCtx},
+    trail::Trail,
 };
 
-/// Represents a single pendulum in the simulation.
+/// State vector of a double pendulum,
+/// laid out as `(theta_1, omega_1, theta_2, omega_2)`.
 ///
-/// The pendulum has a fixed length and damping coefficient, and
-/// tracks its current angle (theta) and angular velocity (omega).
-pub struct DoublePendulum {
+/// Angles are in radians, measured from straight down
+/// according to an `AngleConvention`.
+pub type State = [f64; 4];
+
+/// How the angle of the second rod is measured.
+///
+/// The first rod's angle is always measured from straight down.
+/// The equations of motion and `Observe` use absolute angles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AngleConvention {
+    /// The second rod's angle is measured from straight down.
+    #[default]
+    Absolute,
+    /// The second rod's angle is measured from the direction of the first rod.
+    Relative,
+}
+
+impl AngleConvention {
+    /// Converts a state in this convention to absolute angles.
+    pub fn to_absolute(self, state: State) -> State {
+        match self {
+            Self::Absolute => state,
+            Self::Relative => relative_to_absolute(state),
+        }
+    }
+
+    /// Converts a state in absolute angles to this convention.
+    pub fn from_absolute(self, state: State) -> State {
+        match self {
+            Self::Absolute => state,
+            Self::Relative => absolute_to_relative(state),
+        }
+    }
+}
+
+/// Converts a state from absolute to relative angles, and angular velocities.
+pub fn absolute_to_relative(state: State) -> State {
+    let [theta_1, omega_1, theta_2, omega_2] = state;
+    [theta_1, omega_1, theta_2 - theta_1, omega_2 - omega_1]
+}
+
+/// Converts a state from relative to absolute angles, and angular velocities.
+pub fn relative_to_absolute(state: State) -> State {
+    let [theta_1, omega_1, theta_2, omega_2] = state;
+    [theta_1, omega_1, theta_1 + theta_2, omega_1 + omega_2]
+}
+
+/// Physical parameters of a double pendulum.
+///
+/// Supplies the equations of motion to the integrator
+/// through its `Dynamics` implementation.
+#[derive(Clone, Copy, PartialEq)]
+pub struct DoublePendulumParams {
     pub length_1: f64,
     pub length_2: f64,
-    theta_1: f64,
-    omega_1: f64,
-    theta_2: f64,
-    omega_2: f64,
+    pub mass_1: f64,
+    pub mass_2: f64,
+    pub friction_1: Friction,
+    pub friction_2: Friction,
+}
+
+impl Default for DoublePendulumParams {
+    /// Frictionless 1m rods with 1kg bobs.
+    fn default() -> Self {
+        Self {
+            length_1: 1.0,
+            length_2: 1.0,
+            mass_1: 1.0,
+            mass_2: 1.0,
+            friction_1: Friction::default(),
+            friction_2: Friction::default(),
+        }
+    }
+}
+
+impl DoublePendulumParams {
+    /// Positions of both bobs in meters relative to the pivot,
+    /// with `y` pointing down, in the given absolute state.
+    pub fn positions(&self, state: &State) -> [(f64, f64); 2] {
+        let [theta_1, _, theta_2, _] = *state;
+        let (s_1, c_1) = theta_1.sin_cos();
+        let (s_2, c_2) = theta_2.sin_cos();
+        let (x_1, y_1) = (self.length_1 * s_1, self.length_1 * c_1);
+        [
+            (x_1, y_1),
+            (x_1 + self.length_2 * s_2, y_1 + self.length_2 * c_2),
+        ]
+    }
+
+    /// Kinetic energy of both bobs in the given state.
+    pub fn kinetic_energy(&self, state: &State) -> f64 {
+        let [theta_1, omega_1, theta_2, omega_2] = *state;
+        let (m_1, m_2) = (self.mass_1, self.mass_2);
+        let (l_1, l_2) = (self.length_1, self.length_2);
+        0.5 * (m_1 + m_2) * l_1 * l_1 * omega_1 * omega_1
+            + 0.5 * m_2 * l_2 * l_2 * omega_2 * omega_2
+            + m_2 * l_1 * l_2 * omega_1 * omega_2 * (theta_1 - theta_2).cos()
+    }
+
+    /// Potential energy of both bobs in the given state,
+    /// zero at the pivot height.
+    pub fn potential_energy(&self, state: &State) -> f64 {
+        let [theta_1, _, theta_2, _] = *state;
+        -(self.mass_1 + self.mass_2) * GRAVITY * self.length_1 * theta_1.cos()
+            - self.mass_2 * GRAVITY * self.length_2 * theta_2.cos()
+    }
+
+    /// State with the first rod hanging straight down and swinging
+    /// counterclockwise on screen, at the given total energy.
+    ///
+    /// Solves the energy equation for `omega_1 > 0`, i.e. returns a
+    /// starting point on the Poincaré section `theta_1 = 0`.
+    /// Returns `None` if the second rod's angle and angular
+    /// velocity alone already exceed the energy, or if the first
+    /// rod or both bobs are weightless or of zero length.
+    ///
+    /// # Arguments
+    /// * `energy` - Total energy in joules, zero at the pivot height.
+    /// * `theta_2` - Absolute angle of the second rod.
+    /// * `omega_2` - Absolute angular velocity of the second rod.
+    pub fn state_at_energy(&self, energy: f64, theta_2: f64, omega_2: f64) -> Option<State> {
+        // Kinetic energy is a * omega_1^2 + b * omega_1 + c
+        let (m_1, m_2) = (self.mass_1, self.mass_2);
+        let (l_1, l_2) = (self.length_1, self.length_2);
+        let a = 0.5 * (m_1 + m_2) * l_1 * l_1;
+        if !(a > 0.0 && a.is_finite()) {
+            return None;
+        }
+        let b = m_2 * l_1 * l_2 * omega_2 * theta_2.cos();
+        let c = 0.5 * m_2 * l_2 * l_2 * omega_2 * omega_2
+            + self.potential_energy(&[0.0, 0.0, theta_2, omega_2])
+            - energy;
+        let discriminant = b * b - 4.0 * a * c;
+        let omega_1 = (-b + discriminant.sqrt()) / (2.0 * a);
+        (omega_1 > 0.0).then_some([0.0, omega_1, theta_2, omega_2])
+    }
+
+    /// Angular momentum of both bobs about the pivot in the given state.
+    pub fn angular_momentum(&self, state: &State) -> f64 {
+        let [theta_1, omega_1, theta_2, omega_2] = *state;
+        let (m_1, m_2) = (self.mass_1, self.mass_2);
+        let (l_1, l_2) = (self.length_1, self.length_2);
+        (m_1 + m_2) * l_1 * l_1 * omega_1
+            + m_2 * l_2 * l_2 * omega_2
+            + m_2 * l_1 * l_2 * (theta_1 - theta_2).cos() * (omega_1 + omega_2)
+    }
+}
+
+impl Dynamics for DoublePendulumParams {
+    /// Computes the time derivative of the given state.
+    ///
+    /// This is a pure function of the state vector, applying the
+    /// equations of motion for the double pendulum:
+    ///
+    /// dtheta/dt = omega
+    /// domega/dt = alpha + M⁻¹ torque
+    ///
+    /// where `alpha` is the frictionless acceleration from gravity and the
+    /// coupling between the rods, `M` is the mass matrix, and `torque` holds
+    /// the friction torques on each rod: viscous and Coulomb friction at
+    /// each joint, the second joint acting on both rods with opposite signs
+    /// on their relative rate, plus quadratic air drag on each rod's own rate.
+    fn derivative(&self, state: &[f64], out: &mut [f64]) {
+        let &[theta_1, omega_1, theta_2, omega_2] = state else {
+            panic!("double pendulum state must have 4 components");
+        };
+
+        // Acceleration terms
+        let (m_1, m_2) = (self.mass_1, self.mass_2);
+        let (l_1, l_2) = (self.length_1, self.length_2);
+        let sin_1 = theta_1.sin();
+        let cos_1 = theta_1.cos();
+        let sin_diff = (theta_1 - theta_2).sin();
+        let cos_diff = (theta_1 - theta_2).cos();
+        let denom = 2.0 * m_1 + m_2 - m_2 * (2.0 * (theta_1 - theta_2)).cos();
+        let omega_1_sq = omega_1 * omega_1;
+        let omega_2_sq = omega_2 * omega_2;
+        let alpha_1 = (-GRAVITY * (2.0 * m_1 + m_2) * sin_1
+            - m_2 * GRAVITY * (theta_1 - 2.0 * theta_2).sin()
+            - 2.0 * sin_diff * m_2 * (omega_2_sq * l_2 + omega_1_sq * l_1 * cos_diff))
+            / (l_1 * denom);
+        let alpha_2 = (2.0
+            * sin_diff
+            * (omega_1_sq * l_1 * (m_1 + m_2)
+                + GRAVITY * (m_1 + m_2) * cos_1
+                + omega_2_sq * l_2 * m_2 * cos_diff))
+            / (l_2 * denom);
+
+        // Friction torques. The second joint acts on both rods
+        // with opposite signs, and drag acts on each rod's own rate.
+        let joint_2 = self.friction_2.joint_torque(omega_2 - omega_1);
+        let torque_1 =
+            self.friction_1.joint_torque(omega_1) + self.friction_1.drag_torque(omega_1) - joint_2;
+        let torque_2 = joint_2 + self.friction_2.drag_torque(omega_2);
+
+        // Dampening from torques through the inverse mass matrix
+        let det = m_2 * l_1 * l_1 * l_2 * l_2 * (m_1 + m_2 * sin_diff * sin_diff);
+        let dampening_1 = m_2 * l_2 * (l_2 * torque_1 - l_1 * cos_diff * torque_2) / det;
+        let dampening_2 =
+            l_1 * ((m_1 + m_2) * l_1 * torque_2 - m_2 * l_2 * cos_diff * torque_1) / det;
+
+        out.copy_from_slice(&[
+            omega_1,
+            alpha_1 + dampening_1,
+            omega_2,
+            alpha_2 + dampening_2,
+        ]);
+    }
+}
+
+/// Radius of a bob in meters, or of a 1kg bob when scaling with mass.
+#[cfg(feature = "web")]
+const BOB_RADIUS: f64 = 0.05;
+
+/// Radius of the pivot in meters.
+#[cfg(feature = "web")]
+const PIVOT_RADIUS: f64 = 0.025;
+
+/// Represents a double pendulum in the simulation.
+///
+/// The pendulum has fixed rod lengths, bob masses and joint friction,
+/// and tracks the angles (theta) and angular velocities (omega) of
+/// both rods. The state is integrated in absolute angles, and read
+/// and written in the convention chosen at construction.
+pub struct DoublePendulum {
+    pub params: DoublePendulumParams,
+    convention: AngleConvention,
+    state: State,
+    prev: State,
+    integrator: Box<dyn Integrator>,
+    #[cfg_attr(not(feature = "web"), allow(dead_code))]
+    scale_bobs: bool,
+    initial_energy: f64,
+    drag: Dragger,
+    trail: Option<Trail>,
+    lyapunov: Option<Lyapunov>,
 }
 
 impl DoublePendulum {
-    /// Creates a new DoublePendulum with the specified length,
-    /// damping, initial angle, and initial angular velocity.
-    pub fn new(
-        length_0: f64,
-        length_1: f64,
-        theta_0: f64,
-        omega_0: f64,
-        theta_1: f64,
-        omega_1: f64,
+    /// Creates a new DoublePendulum with the specified physical
+    /// parameters and initial state, in absolute angles.
+    ///
+    /// Integrates with `Rk4` unless configured otherwise.
+    ///
+    /// # Arguments
+    /// * `params` - Rod lengths, bob masses and joint friction.
+    /// * `state` - Initial `(theta_1, omega_1, theta_2, omega_2)`.
+    pub fn new(params: DoublePendulumParams, state: State) -> Self {
+        Self::with_convention(params, state, AngleConvention::Absolute)
+    }
+
+    /// Creates a new DoublePendulum with the specified physical
+    /// parameters and initial state, in the given angle convention.
+    ///
+    /// # Arguments
+    /// * `params` - Rod lengths, bob masses and joint friction.
+    /// * `state` - Initial `(theta_1, omega_1, theta_2, omega_2)`.
+    /// * `convention` - How `theta_2` and `omega_2` are measured, both
+    ///   in `state` and in the states returned by `state`.
+    pub fn with_convention(
+        params: DoublePendulumParams,
+        state: State,
+        convention: AngleConvention,
     ) -> Self {
+        let state = convention.to_absolute(state);
         Self {
-            length_1: length_0,
-            length_2: length_1,
-            theta_1: theta_0,
-            omega_1: omega_0,
-            theta_2: theta_1,
-            omega_2: omega_1,
+            params,
+            convention,
+            state,
+            prev: state,
+            integrator: Box::new(Rk4),
+            scale_bobs: false,
+            initial_energy: params.kinetic_energy(&state) + params.potential_energy(&state),
+            drag: Dragger::default(),
+            trail: None,
+            lyapunov: None,
+        }
+    }
+
+    /// Returns the current state, in the angle convention chosen at construction.
+    pub fn state(&self) -> State {
+        self.convention.from_absolute(self.state)
+    }
+
+    /// Returns the angle convention chosen at construction.
+    pub fn convention(&self) -> AngleConvention {
+        self.convention
+    }
+
+    /// Sets the integrator used to advance the simulation.
+    pub fn integrator(self, integrator: impl Integrator + 'static) -> Self {
+        Self {
+            integrator: Box::new(integrator),
+            ..self
+        }
+    }
+
+    /// Sets whether bob radii scale with mass when rendering.
+    ///
+    /// Scaled bobs have constant density, so their radius
+    /// grows with the cube root of their mass.
+    pub fn scale_bobs(self, scale_bobs: bool) -> Self {
+        Self { scale_bobs, ..self }
+    }
+
+    /// Sets whether a dragged bob keeps its velocity when released.
+    pub fn throw(self, throw: bool) -> Self {
+        Self {
+            drag: Dragger::new(throw),
+            ..self
+        }
+    }
+
+    /// Sets a trail to draw behind the bobs.
+    pub fn trail(self, trail: Trail) -> Self {
+        Self {
+            trail: Some(trail),
+            ..self
+        }
+    }
+
+    /// Sets a shadow trajectory tracker estimating the maximal Lyapunov
+    /// exponent, reported through `Diagnostics::lyapunov_exponent`.
+    ///
+    /// The estimate restarts whenever a dragged bob is released.
+    pub fn lyapunov(self, mut lyapunov: Lyapunov) -> Self {
+        lyapunov.reset(&self.state);
+        Self {
+            lyapunov: Some(lyapunov),
+            ..self
+        }
+    }
+
+    /// Positions of both bobs in meters relative to the pivot, interpolated
+    /// between the last two physics states for rendering.
+    #[cfg(feature = "web")]
+    pub fn interpolated_positions(&self, render: &RenderCtx) -> [(f64, f64); 2] {
+        self.params
+            .positions(&render.interpolate(&self.prev, &self.state))
+    }
+
+    /// Computes the rendered radius in meters of a bob with the given mass.
+    #[cfg(feature = "web")]
+    fn bob_radius(&self, mass: f64) -> f64 {
+        if self.scale_bobs {
+            BOB_RADIUS * mass.cbrt()
+        } else {
+            BOB_RADIUS
         }
     }
 }
@@ -43,73 +377,156 @@ impl Simulation for DoublePendulum {
     /// Renders the pendulum on the provided render context.
     ///
     /// Draws the pivot point, the rod, and the bob of the pendulum on the canvas.
+    #[cfg(feature = "web")]
     fn render(&self, render: &RenderCtx) {
-        // Pivot
-        let x_0 = (render.window.canvas.width() as f64) / 2.0;
-        let y_0 = (render.window.canvas.height() as f64) * (1.0 / 4.0);
+        let [node_1, node_2] = self.interpolated_positions(render);
 
-        // First Node
-        let (s, c) = self.theta_1.sin_cos();
-        let x_1 = x_0 + self.length_1 * METERS_TO_PIXELS * s;
-        let y_1 = y_0 + self.length_1 * METERS_TO_PIXELS * c;
+        // Nodes
+        let (x_0, y_0) = render.camera.to_screen((0.0, 0.0));
+        let (x_1, y_1) = render.camera.to_screen(node_1);
+        let (x_2, y_2) = render.camera.to_screen(node_2);
 
-        // Second Node
-        let (s, c) = (self.theta_1 + self.theta_2).sin_cos();
-        let x_2 = x_1 + self.length_2 * METERS_TO_PIXELS * s;
-        let y_2 = y_1 + self.length_2 * METERS_TO_PIXELS * c;
+        // Sizes
+        let pivot_radius = render.camera.to_pixels(PIVOT_RADIUS);
+        let bob_radius_1 = render.camera.to_pixels(self.bob_radius(self.params.mass_1));
+        let bob_radius_2 = render.camera.to_pixels(self.bob_radius(self.params.mass_2));
+        let rod_width = render.camera.to_pixels(ROD_WIDTH);
 
         // Render
         render.clear();
-        render.draw(&Line(x_0, y_0, x_1, y_1).styled().stroke("#ffff00".into()));
-        render.draw(&Line(x_1, y_1, x_2, y_2).styled().stroke("#ff00ff".into()));
+        if let Some(trail) = &self.trail {
+            trail.render(render);
+        }
+        render.draw(
+            &Line(x_0, y_0, x_1, y_1)
+                .styled()
+                .stroke(Color::hex(0xffff00).into())
+                .line_width(rod_width)
+                .line_cap(LineCap::Round),
+        );
         render.draw(
-            &FilledCircle((x_0, y_0), 5.0)
+            &Line(x_1, y_1, x_2, y_2)
                 .styled()
-                .fill("#ffffff".into()),
+                .stroke(Color::hex(0xff00ff).into())
+                .line_width(rod_width)
+                .line_cap(LineCap::Round),
         );
         render.draw(
-            &FilledCircle((x_1, y_1), 10.0)
+            &FilledCircle((x_0, y_0), pivot_radius)
                 .styled()
-                .fill("#00ff00".into()),
+                .fill(Color::WHITE.into()),
         );
         render.draw(
-            &FilledCircle((x_2, y_2), 10.0)
+            &FilledCircle((x_1, y_1), bob_radius_1)
                 .styled()
-                .fill("#00aaff".into()),
+                .fill(Color::hex(0x00ff00).into())
+                .shadow_blur(bob_radius_1)
+                .shadow_color(Color::hex(0x00ff00)),
+        );
+        render.draw(
+            &FilledCircle((x_2, y_2), bob_radius_2)
+                .styled()
+                .fill(Color::hex(0x00aaff).into())
+                .shadow_blur(bob_radius_2)
+                .shadow_color(Color::hex(0x00aaff)),
         );
     }
 
-    /// Updates the pendulum's state based on the update context.
+    /// Lets either bob be dragged around with the pointer.
     ///
-    /// Applies the physics equations to update
-    /// the angle and angular velocity for the next frame.
+    /// Energy is measured from the moment the bob is released.
+    #[cfg(feature = "web")]
+    fn input(&mut self, input: &InputCtx) {
+        let lengths = [self.params.length_1, self.params.length_2];
+        for event in input.events {
+            let target = input.to_world(event);
+            if self.drag.handle(event, target, &lengths, &mut self.state) {
+                self.initial_energy = self.total_energy();
+                if let Some(lyapunov) = &mut self.lyapunov {
+                    lyapunov.reset(&self.state);
+                }
+            }
+        }
+        if self.drag.is_dragging() {
+            input.claim();
+        }
+    }
+
+    /// The pendulum can reach both rod lengths from the pivot.
+    #[cfg(feature = "web")]
+    fn bounds(&self) -> Option<Bounds> {
+        Some(Bounds::around_pivot(
+            self.params.length_1 + self.params.length_2,
+        ))
+    }
+
+    /// Updates the pendulum's state based on the update context.
     ///
-    /// dtheta/dt = omega
-    /// domega/dt = -(gravity contribution) - dampening
+    /// Advances the state by one fixed step using the configured integrator,
+    /// and reports the integrator's step counts to the context.
+    /// Holds still while a bob is being dragged.
     fn update(&mut self, update: &UpdateCtx) {
-        // time delta
-        let dt = update.frame.dt;
+        self.prev = self.state;
+        if self.drag.is_dragging() {
+            return;
+        }
+        let stats = self
+            .integrator
+            .step(&self.params, &mut self.state, update.dt);
+        update.record(stats);
+        if let Some(lyapunov) = &mut self.lyapunov {
+            lyapunov.step(&self.params, &self.state, update.dt);
+        }
+        if let Some(mut trail) = self.trail.take() {
+            trail.push(&self.joints());
+            self.trail = Some(trail);
+        }
+    }
 
-        // Acceleration terms
-        let sin_1 = self.theta_1.sin();
-        let cos_1 = self.theta_1.cos();
-        let sin_diff = (self.theta_1 - self.theta_2).sin();
-        let cos_diff = (self.theta_1 - self.theta_2).cos();
-        let denom = 2.0 - cos_diff * cos_diff;
-        let omega_1_sq = self.omega_1 * self.omega_1;
-        let omega_2_sq = self.omega_2 * self.omega_2;
-        let alpha_1 = (-GRAVITY * (2.0 * sin_1 - sin_diff * cos_diff)
-            - omega_2_sq * self.length_2 * sin_diff
-            - omega_1_sq * self.length_1 * sin_1 * cos_diff)
-            / (self.length_1 * denom);
-        let alpha_2 = (2.0 * sin_diff * (omega_1_sq * self.length_1 * cos_diff + GRAVITY * cos_1)
-            + omega_2_sq * self.length_2 * sin_diff * cos_diff)
-            / (self.length_2 * denom);
-
-        // Theta and omega updates
-        self.theta_1 += self.omega_1 * dt;
-        self.omega_1 += alpha_1 * dt;
-        self.theta_2 += self.omega_2 * dt;
-        self.omega_2 += alpha_2 * dt;
+    fn diagnostics(&self) -> Option<&dyn Diagnostics> {
+        Some(self)
+    }
+}
+
+impl Diagnostics for DoublePendulum {
+    fn kinetic_energy(&self) -> f64 {
+        self.params.kinetic_energy(&self.state)
+    }
+
+    fn potential_energy(&self) -> f64 {
+        self.params.potential_energy(&self.state)
+    }
+
+    fn initial_energy(&self) -> f64 {
+        self.initial_energy
+    }
+
+    fn angular_momentum(&self) -> f64 {
+        self.params.angular_momentum(&self.state)
+    }
+
+    fn lyapunov_exponent(&self) -> Option<f64> {
+        self.lyapunov.as_ref().and_then(Lyapunov::exponent)
+    }
+}
+
+impl Observe for DoublePendulum {
+    fn joints(&self) -> Vec<Joint> {
+        let [theta_1, omega_1, theta_2, omega_2] = self.state;
+        let [(x_1, y_1), (x_2, y_2)] = self.params.positions(&self.state);
+        vec![
+            Joint {
+                theta: theta_1,
+                omega: omega_1,
+                x: x_1,
+                y: y_1,
+            },
+            Joint {
+                theta: theta_2,
+                omega: omega_2,
+                x: x_2,
+                y: y_2,
+            },
+        ]
     }
 }
@@ -1,5 +1,7 @@
-use crate::window::WindowCtx;
+use crate::{color::Color, window::WindowCtx};
 use std::f64::consts::TAU;
+use wasm_bindgen::JsValue;
+use web_sys::{CanvasGradient, CanvasRenderingContext2d};
 
 /// Trait for objects that can be drawn on a canvas.
 ///
@@ -75,6 +77,513 @@ impl Draw for Line {
 
 impl Style for Line {}
 
+/// An open polyline drawable.
+///
+/// Represents connected line segments through a list of points.
+/// The polyline is stroked using the current stroke style of the canvas context.
+///
+/// # Fields
+/// * `0` - The points, in drawing order.
+pub struct Polyline(pub Vec<(f64, f64)>);
+
+impl Draw for Polyline {
+    /// Draws line segments between consecutive points.
+    ///
+    /// The polyline is stroked using the current stroke style of the canvas context.
+    fn draw(&self, window: &WindowCtx) {
+        let Self(points) = self;
+        trace(window, points);
+        window.ctx.stroke();
+    }
+}
+
+impl Style for Polyline {}
+
+/// A filled polygon drawable.
+///
+/// Represents the closed shape outlined by a list of points.
+/// The polygon is filled using the current fill style of the canvas context.
+///
+/// # Fields
+/// * `0` - The corners, in drawing order.
+pub struct Polygon(pub Vec<(f64, f64)>);
+
+impl Draw for Polygon {
+    /// Draws the polygon through its corners, closing it back to the first.
+    ///
+    /// The polygon is filled using the current fill style of the canvas context.
+    fn draw(&self, window: &WindowCtx) {
+        let Self(points) = self;
+        trace(window, points);
+        window.ctx.close_path();
+        window.ctx.fill();
+    }
+}
+
+impl Style for Polygon {}
+
+/// Begins a path through the given points.
+fn trace(window: &WindowCtx, points: &[(f64, f64)]) {
+    window.ctx.begin_path();
+    for (i, &(x, y)) in points.iter().enumerate() {
+        if i == 0 {
+            window.ctx.move_to(x, y);
+        } else {
+            window.ctx.line_to(x, y);
+        }
+    }
+}
+
+/// A filled rectangle drawable.
+///
+/// Represents an axis-aligned rectangle.
+/// The rectangle is filled using the current fill style of the canvas context.
+///
+/// # Fields
+/// * `0` - The top-left corner as (x, y) coordinates.
+/// * `1` - The width and height.
+pub struct Rect(pub (f64, f64), pub (f64, f64));
+
+impl Draw for Rect {
+    /// Draws a filled rectangle at the specified corner and size.
+    ///
+    /// The rectangle is filled using the current fill style of the canvas context.
+    fn draw(&self, window: &WindowCtx) {
+        let Self((x, y), (w, h)) = self;
+        window.ctx.fill_rect(*x, *y, *w, *h);
+    }
+}
+
+impl Style for Rect {}
+
+/// A stroked circle drawable.
+///
+/// Represents the outline of a circle with a center point and radius.
+/// The circle is stroked using the current stroke style of the canvas context.
+///
+/// # Fields
+/// * `0` - The center point as (x, y) coordinates.
+/// * `1` - The radius of the circle.
+pub struct Circle(pub (f64, f64), pub f64);
+
+impl Draw for Circle {
+    /// Draws the outline of a circle at the specified center and radius.
+    ///
+    /// The circle is stroked using the current stroke style of the canvas context.
+    fn draw(&self, window: &WindowCtx) {
+        let Self((x, y), r) = self;
+        window.ctx.begin_path();
+        window.ctx.arc(*x, *y, *r, 0.0, TAU).unwrap();
+        window.ctx.stroke();
+    }
+}
+
+impl Style for Circle {}
+
+/// A circular arc drawable, e.g. for indicating angles.
+///
+/// Represents part of a circle between two angles, in radians
+/// clockwise from the positive x-axis. Sweeps from the start angle
+/// to the end angle, clockwise if the end angle is larger and
+/// anticlockwise otherwise.
+/// The arc is stroked using the current stroke style of the canvas context.
+///
+/// # Fields
+/// * `0` - The center point as (x, y) coordinates.
+/// * `1` - The radius of the arc.
+/// * `2` - The start angle.
+/// * `3` - The end angle.
+pub struct Arc(pub (f64, f64), pub f64, pub f64, pub f64);
+
+impl Draw for Arc {
+    /// Draws the arc between the two specified angles.
+    ///
+    /// The arc is stroked using the current stroke style of the canvas context.
+    fn draw(&self, window: &WindowCtx) {
+        let Self((x, y), r, start, end) = self;
+        window.ctx.begin_path();
+        window
+            .ctx
+            .arc_with_anticlockwise(*x, *y, *r, *start, *end, end < start)
+            .unwrap();
+        window.ctx.stroke();
+    }
+}
+
+impl Style for Arc {}
+
+/// A text drawable.
+///
+/// Represents a single line of text anchored at a point, positioned relative
+/// to it according to the `text_align` and `text_baseline` style options.
+/// The text is filled using the current fill style and font of the canvas context.
+///
+/// # Fields
+/// * `0` - The anchor point as (x, y) coordinates.
+/// * `1` - The text.
+pub struct Text(pub (f64, f64), pub String);
+
+impl Draw for Text {
+    /// Draws the text at the specified anchor point.
+    ///
+    /// The text is filled using the current fill style and font of the canvas context.
+    fn draw(&self, window: &WindowCtx) {
+        let Self((x, y), text) = self;
+        window.ctx.fill_text(text, *x, *y).unwrap();
+    }
+}
+
+impl Style for Text {}
+
+/// A single command of a `Path`.
+#[derive(Clone, Copy, Debug)]
+enum Segment {
+    MoveTo(f64, f64),
+    LineTo(f64, f64),
+    QuadraticTo(f64, f64, f64, f64),
+    BezierTo(f64, f64, f64, f64, f64, f64),
+    Arc(f64, f64, f64, f64, f64),
+    Close,
+}
+
+/// A general path drawable, built up segment by segment.
+///
+/// The path is stroked using the current stroke style of the
+/// canvas context, or filled using the fill style if `filled`.
+///
+/// # Example
+/// ```ignore
+/// let arrow = Path::new()
+///     .move_to(0.0, 0.0)
+///     .line_to(10.0, 5.0)
+///     .line_to(0.0, 10.0)
+///     .close()
+///     .filled();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Path {
+    segments: Vec<Segment>,
+    filled: bool,
+}
+
+impl Path {
+    /// Creates an empty path.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a segment to the path.
+    fn segment(mut self, segment: Segment) -> Self {
+        self.segments.push(segment);
+        self
+    }
+
+    /// Starts a new subpath at the given point.
+    pub fn move_to(self, x: f64, y: f64) -> Self {
+        self.segment(Segment::MoveTo(x, y))
+    }
+
+    /// Adds a straight line to the given point.
+    pub fn line_to(self, x: f64, y: f64) -> Self {
+        self.segment(Segment::LineTo(x, y))
+    }
+
+    /// Adds a quadratic Bézier curve to `(x, y)` with the control point `(cx, cy)`.
+    pub fn quadratic_to(self, cx: f64, cy: f64, x: f64, y: f64) -> Self {
+        self.segment(Segment::QuadraticTo(cx, cy, x, y))
+    }
+
+    /// Adds a cubic Bézier curve to `(x, y)` with the
+    /// control points `(cx_0, cy_0)` and `(cx_1, cy_1)`.
+    pub fn bezier_to(self, cx_0: f64, cy_0: f64, cx_1: f64, cy_1: f64, x: f64, y: f64) -> Self {
+        self.segment(Segment::BezierTo(cx_0, cy_0, cx_1, cy_1, x, y))
+    }
+
+    /// Adds a circular arc, swept as for the `Arc` drawable.
+    ///
+    /// # Arguments
+    /// * `center` - The center point as (x, y) coordinates.
+    /// * `r` - The radius of the arc.
+    /// * `start` - The start angle.
+    /// * `end` - The end angle.
+    pub fn arc(self, (x, y): (f64, f64), r: f64, start: f64, end: f64) -> Self {
+        self.segment(Segment::Arc(x, y, r, start, end))
+    }
+
+    /// Closes the current subpath with a straight line back to its start.
+    pub fn close(self) -> Self {
+        self.segment(Segment::Close)
+    }
+
+    /// Fills the path instead of stroking it.
+    pub fn filled(self) -> Self {
+        Self {
+            filled: true,
+            ..self
+        }
+    }
+}
+
+impl Draw for Path {
+    /// Draws the path segment by segment.
+    ///
+    /// The path is stroked using the current stroke style of the
+    /// canvas context, or filled using the fill style if `filled`.
+    fn draw(&self, window: &WindowCtx) {
+        let ctx = &window.ctx;
+        ctx.begin_path();
+        for segment in &self.segments {
+            match *segment {
+                Segment::MoveTo(x, y) => ctx.move_to(x, y),
+                Segment::LineTo(x, y) => ctx.line_to(x, y),
+                Segment::QuadraticTo(cx, cy, x, y) => ctx.quadratic_curve_to(cx, cy, x, y),
+                Segment::BezierTo(cx_0, cy_0, cx_1, cy_1, x, y) => {
+                    ctx.bezier_curve_to(cx_0, cy_0, cx_1, cy_1, x, y)
+                }
+                Segment::Arc(x, y, r, start, end) => ctx
+                    .arc_with_anticlockwise(x, y, r, start, end, end < start)
+                    .unwrap(),
+                Segment::Close => ctx.close_path(),
+            }
+        }
+        if self.filled {
+            ctx.fill();
+        } else {
+            ctx.stroke();
+        }
+    }
+}
+
+impl Style for Path {}
+
+/// A gradient for filling or stroking shapes.
+///
+/// Coordinates are in canvas space, and color stops are
+/// placed at offsets between 0 and 1 along the gradient.
+#[derive(Clone)]
+pub enum Gradient {
+    /// Blends along the line from `start` to `end`.
+    Linear {
+        start: (f64, f64),
+        end: (f64, f64),
+        stops: Vec<(f64, Color)>,
+    },
+    /// Blends between the circle `(x, y, radius)` at
+    /// `start` and the one at `end`.
+    Radial {
+        start: (f64, f64, f64),
+        end: (f64, f64, f64),
+        stops: Vec<(f64, Color)>,
+    },
+}
+
+impl Gradient {
+    /// Creates a linear gradient from `start` to `end`, without color stops.
+    pub fn linear(start: (f64, f64), end: (f64, f64)) -> Self {
+        Self::Linear {
+            start,
+            end,
+            stops: Vec::new(),
+        }
+    }
+
+    /// Creates a radial gradient between two circles
+    /// given as `(x, y, radius)`, without color stops.
+    pub fn radial(start: (f64, f64, f64), end: (f64, f64, f64)) -> Self {
+        Self::Radial {
+            start,
+            end,
+            stops: Vec::new(),
+        }
+    }
+
+    /// Adds a color stop at an offset between 0 and 1.
+    pub fn stop(mut self, offset: f64, color: Color) -> Self {
+        match &mut self {
+            Self::Linear { stops, .. } | Self::Radial { stops, .. } => stops.push((offset, color)),
+        }
+        self
+    }
+
+    /// Creates the gradient on the canvas context.
+    fn create(&self, ctx: &CanvasRenderingContext2d) -> CanvasGradient {
+        let (gradient, stops) = match self {
+            Self::Linear { start, end, stops } => (
+                ctx.create_linear_gradient(start.0, start.1, end.0, end.1),
+                stops,
+            ),
+            Self::Radial { start, end, stops } => (
+                ctx.create_radial_gradient(start.0, start.1, start.2, end.0, end.1, end.2)
+                    .unwrap(),
+                stops,
+            ),
+        };
+        for (offset, color) in stops {
+            gradient
+                .add_color_stop(*offset as f32, &color.to_string())
+                .unwrap();
+        }
+        gradient
+    }
+}
+
+/// What to fill or stroke shapes with.
+#[derive(Clone)]
+pub enum Paint {
+    /// A solid color.
+    Color(Color),
+    /// A linear or radial gradient.
+    Gradient(Gradient),
+}
+
+impl From<Color> for Paint {
+    fn from(color: Color) -> Self {
+        Self::Color(color)
+    }
+}
+
+impl From<Gradient> for Paint {
+    fn from(gradient: Gradient) -> Self {
+        Self::Gradient(gradient)
+    }
+}
+
+impl Paint {
+    /// Sets this paint as the fill style of the canvas context.
+    fn apply_fill(&self, ctx: &CanvasRenderingContext2d) {
+        match self {
+            Self::Color(color) => ctx.set_fill_style_str(&color.to_string()),
+            Self::Gradient(gradient) => ctx.set_fill_style_canvas_gradient(&gradient.create(ctx)),
+        }
+    }
+
+    /// Sets this paint as the stroke style of the canvas context.
+    fn apply_stroke(&self, ctx: &CanvasRenderingContext2d) {
+        match self {
+            Self::Color(color) => ctx.set_stroke_style_str(&color.to_string()),
+            Self::Gradient(gradient) => ctx.set_stroke_style_canvas_gradient(&gradient.create(ctx)),
+        }
+    }
+}
+
+/// Shape drawn at the ends of stroked lines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+impl LineCap {
+    /// Returns the canvas name of the line cap.
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Butt => "butt",
+            Self::Round => "round",
+            Self::Square => "square",
+        }
+    }
+}
+
+/// Shape drawn where stroked line segments meet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+impl LineJoin {
+    /// Returns the canvas name of the line join.
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Miter => "miter",
+            Self::Round => "round",
+            Self::Bevel => "bevel",
+        }
+    }
+}
+
+/// How drawings are composited onto what is already on the canvas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Composite {
+    /// Draws over existing content, the default.
+    SourceOver,
+    /// Draws only where it overlaps existing content.
+    SourceAtop,
+    /// Draws behind existing content.
+    DestinationOver,
+    /// Erases existing content where drawn.
+    DestinationOut,
+    /// Adds colors, making overlaps brighter.
+    Lighter,
+    /// Multiplies colors, making overlaps darker.
+    Multiply,
+    /// Inverts, multiplies and inverts colors, making overlaps lighter.
+    Screen,
+    /// Keeps the lightest of both colors.
+    Lighten,
+    /// Keeps the darkest of both colors.
+    Darken,
+}
+
+impl Composite {
+    /// Returns the canvas name of the composite operation.
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::SourceOver => "source-over",
+            Self::SourceAtop => "source-atop",
+            Self::DestinationOver => "destination-over",
+            Self::DestinationOut => "destination-out",
+            Self::Lighter => "lighter",
+            Self::Multiply => "multiply",
+            Self::Screen => "screen",
+            Self::Lighten => "lighten",
+            Self::Darken => "darken",
+        }
+    }
+}
+
+/// Horizontal alignment of text relative to its anchor point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlign {
+    /// Returns the canvas name of the text alignment.
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Left => "left",
+            Self::Center => "center",
+            Self::Right => "right",
+        }
+    }
+}
+
+/// Vertical alignment of text relative to its anchor point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextBaseline {
+    Top,
+    Middle,
+    Alphabetic,
+    Bottom,
+}
+
+impl TextBaseline {
+    /// Returns the canvas name of the text baseline.
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Top => "top",
+            Self::Middle => "middle",
+            Self::Alphabetic => "alphabetic",
+            Self::Bottom => "bottom",
+        }
+    }
+}
+
 /// A wrapper for applying styling to drawable objects.
 ///
 /// This struct applies fill and stroke styles to any drawable object
@@ -88,8 +597,19 @@ pub struct Styled<C: Draw> {
     contained: C,
 
     // Style Options
-    fill: Option<String>,
-    stroke: Option<String>,
+    fill: Option<Paint>,
+    stroke: Option<Paint>,
+    line_width: Option<f64>,
+    line_cap: Option<LineCap>,
+    line_join: Option<LineJoin>,
+    line_dash: Option<Vec<f64>>,
+    global_alpha: Option<f64>,
+    shadow_blur: Option<f64>,
+    shadow_color: Option<Color>,
+    composite: Option<Composite>,
+    font: Option<String>,
+    text_align: Option<TextAlign>,
+    text_baseline: Option<TextBaseline>,
 }
 
 /// Macro for generating style builder methods and an `apply_style` helper.
@@ -100,22 +620,23 @@ pub struct Styled<C: Draw> {
 ///
 /// # Arguments
 /// - `$opt:ident` - The field name (e.g., `fill`, `stroke`)
-/// - `$typ:ty` - The field type (e.g., `String`)
-/// - `$ctxmethod:ident` - The canvas context method to call (e.g., `set_fill_style_str`)
+/// - `$typ:ty` - The field type (e.g., `Paint`)
+/// - `|$ctx, $value| $apply` - Applies a reference to the value to the canvas context
 ///
 /// # Example
 /// ```ignore
 /// handle_opts![
-///     fill: String => set_fill_style_str,
-///     stroke: String => set_stroke_style_str
+///     fill: Paint => |ctx, paint| paint.apply_fill(ctx),
+///     line_width: f64 => |ctx, width| ctx.set_line_width(*width)
 /// ];
 /// ```
 macro_rules! handle_opts {
-    ($($opt:ident: $typ:ty => $ctxmethod:ident),+) => {
+    ($($opt:ident: $typ:ty => |$ctx:ident, $value:ident| $apply:expr),+ $(,)?) => {
         fn apply_style(&self, window: &WindowCtx) {
             $(
-                if let Some($opt) = &self.$opt {
-                    window.ctx.$ctxmethod(&$opt);
+                if let Some($value) = &self.$opt {
+                    let $ctx = &window.ctx;
+                    $apply;
                 }
             )+
         }
@@ -140,12 +661,37 @@ impl<C: Draw> Styled<C> {
             contained,
             fill: None,
             stroke: None,
+            line_width: None,
+            line_cap: None,
+            line_join: None,
+            line_dash: None,
+            global_alpha: None,
+            shadow_blur: None,
+            shadow_color: None,
+            composite: None,
+            font: None,
+            text_align: None,
+            text_baseline: None,
         }
     }
 
     handle_opts![
-        fill: String => set_fill_style_str,
-        stroke: String => set_stroke_style_str
+        fill: Paint => |ctx, paint| paint.apply_fill(ctx),
+        stroke: Paint => |ctx, paint| paint.apply_stroke(ctx),
+        line_width: f64 => |ctx, width| ctx.set_line_width(*width),
+        line_cap: LineCap => |ctx, cap| ctx.set_line_cap(cap.as_str()),
+        line_join: LineJoin => |ctx, join| ctx.set_line_join(join.as_str()),
+        line_dash: Vec<f64> => |ctx, dash| {
+            let segments: js_sys::Array = dash.iter().map(|&d| JsValue::from(d)).collect();
+            ctx.set_line_dash(&segments).unwrap()
+        },
+        global_alpha: f64 => |ctx, alpha| ctx.set_global_alpha(*alpha),
+        shadow_blur: f64 => |ctx, blur| ctx.set_shadow_blur(*blur),
+        shadow_color: Color => |ctx, color| ctx.set_shadow_color(&color.to_string()),
+        composite: Composite => |ctx, op| ctx.set_global_composite_operation(op.as_str()).unwrap(),
+        font: String => |ctx, font| ctx.set_font(font),
+        text_align: TextAlign => |ctx, align| ctx.set_text_align(align.as_str()),
+        text_baseline: TextBaseline => |ctx, baseline| ctx.set_text_baseline(baseline.as_str()),
     ];
 }
 
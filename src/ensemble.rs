@@ -0,0 +1,196 @@
+use std::cell::Cell;
+
+#[cfg(feature = "web")]
+use crate::{
+    camera::Bounds,
+    color::Color,
+    consts::ROD_WIDTH,
+    draw::{FilledCircle, LineCap, LineJoin, Polyline, Style},
+    sim::RenderCtx,
+};
+use crate::{
+    color::Colormap,
+    diagnostics::Diagnostics,
+    double_pendulum::{DoublePendulum, DoublePendulumParams, State},
+    integrator::{Integrator, StepStats},
+    record::{Joint, Observe},
+    sim::{Simulation, UpdateCtx},
+};
+
+/// Radius of each member's outer bob in meters.
+#[cfg(feature = "web")]
+const BOB_RADIUS: f64 = 0.02;
+
+/// Radius of the shared pivot in meters.
+#[cfg(feature = "web")]
+const PIVOT_RADIUS: f64 = 0.025;
+
+/// Opacity of each member, so overlapping members blend.
+#[cfg(feature = "web")]
+const MEMBER_ALPHA: f64 = 0.7;
+
+/// An ensemble of double pendulums started from nearly the same state.
+///
+/// Shows sensitive dependence on initial conditions: members share their
+/// parameters and pivot, step in lockstep, and start from states differing
+/// by a tiny offset, so they move as one until chaos pulls them apart.
+/// Drawn overlaid in the colors of a colormap, rainbow by default.
+///
+/// The first member starts from the unperturbed state, and is the
+/// reference reported through `Diagnostics` and `Observe`.
+pub struct Ensemble {
+    members: Vec<DoublePendulum>,
+    #[cfg_attr(not(feature = "web"), allow(dead_code))]
+    colormap: Colormap,
+}
+
+impl Ensemble {
+    /// Creates a new Ensemble of double pendulums.
+    ///
+    /// Members integrate with `Rk4` unless configured otherwise.
+    ///
+    /// # Arguments
+    /// * `params` - Rod lengths, bob masses and joint friction of every member.
+    /// * `state` - Initial state of the first member, in absolute angles.
+    /// * `count` - Number of members, at least one.
+    /// * `offset` - Difference between the initial states of consecutive members,
+    ///   so member `i` starts from `state + i * offset`.
+    pub fn new(params: DoublePendulumParams, state: State, count: usize, offset: State) -> Self {
+        let members = (0..count.max(1))
+            .map(|i| {
+                let state = std::array::from_fn(|j| state[j] + i as f64 * offset[j]);
+                DoublePendulum::new(params, state)
+            })
+            .collect();
+        Self {
+            members,
+            colormap: Colormap::Rainbow,
+        }
+    }
+
+    /// Sets the integrator of every member.
+    ///
+    /// # Arguments
+    /// * `make` - Creates a separate integrator for each member.
+    pub fn integrator(self, make: impl Fn() -> Box<dyn Integrator>) -> Self {
+        Self {
+            members: self
+                .members
+                .into_iter()
+                .map(|member| member.integrator(make()))
+                .collect(),
+            ..self
+        }
+    }
+
+    /// Sets the colormap the members are colored by, from the first to the last.
+    pub fn colormap(self, colormap: Colormap) -> Self {
+        Self { colormap, ..self }
+    }
+
+    /// Returns the members, the unperturbed reference first.
+    pub fn members(&self) -> &[DoublePendulum] {
+        &self.members
+    }
+
+    /// Returns the unperturbed reference member.
+    fn reference(&self) -> &DoublePendulum {
+        &self.members[0]
+    }
+}
+
+impl Simulation for Ensemble {
+    /// Renders every member around the shared pivot.
+    ///
+    /// Draws each member's rods and outer bob in its color, translucent
+    /// so members overlapping while still in step blend together.
+    #[cfg(feature = "web")]
+    fn render(&self, render: &RenderCtx) {
+        let pivot = render.camera.to_screen((0.0, 0.0));
+        let rod_width = render.camera.to_pixels(ROD_WIDTH / 2.0);
+        let bob_radius = render.camera.to_pixels(BOB_RADIUS);
+        let pivot_radius = render.camera.to_pixels(PIVOT_RADIUS);
+        let last = (self.members.len() - 1).max(1) as f64;
+
+        render.clear();
+        for (i, member) in self.members.iter().enumerate() {
+            let color = self.colormap.sample(i as f64 / last);
+            let [node_1, node_2] = member.interpolated_positions(render);
+            let (node_1, node_2) = (
+                render.camera.to_screen(node_1),
+                render.camera.to_screen(node_2),
+            );
+            render.draw(
+                &Polyline(vec![pivot, node_1, node_2])
+                    .styled()
+                    .stroke(color.into())
+                    .line_width(rod_width)
+                    .line_cap(LineCap::Round)
+                    .line_join(LineJoin::Round)
+                    .global_alpha(MEMBER_ALPHA),
+            );
+            render.draw(
+                &FilledCircle(node_2, bob_radius)
+                    .styled()
+                    .fill(color.into())
+                    .global_alpha(MEMBER_ALPHA),
+            );
+        }
+        render.draw(
+            &FilledCircle(pivot, pivot_radius)
+                .styled()
+                .fill(Color::WHITE.into()),
+        );
+    }
+
+    /// Every member can reach both rod lengths from the pivot.
+    #[cfg(feature = "web")]
+    fn bounds(&self) -> Option<Bounds> {
+        self.reference().bounds()
+    }
+
+    /// Advances every member by one fixed step, in lockstep.
+    ///
+    /// Only the reference member's integrator step counts are recorded,
+    /// so they stay comparable with a single double pendulum.
+    fn update(&mut self, update: &UpdateCtx) {
+        let (reference, others) = self.members.split_first_mut().unwrap();
+        reference.update(update);
+        let scratch = UpdateCtx {
+            frame: update.frame,
+            dt: update.dt,
+            steps: Cell::new(StepStats::default()),
+        };
+        for member in others {
+            member.update(&scratch);
+        }
+    }
+
+    fn diagnostics(&self) -> Option<&dyn Diagnostics> {
+        Some(self)
+    }
+}
+
+impl Diagnostics for Ensemble {
+    fn kinetic_energy(&self) -> f64 {
+        self.reference().kinetic_energy()
+    }
+
+    fn potential_energy(&self) -> f64 {
+        self.reference().potential_energy()
+    }
+
+    fn initial_energy(&self) -> f64 {
+        self.reference().initial_energy()
+    }
+
+    fn angular_momentum(&self) -> f64 {
+        self.reference().angular_momentum()
+    }
+}
+
+impl Observe for Ensemble {
+    fn joints(&self) -> Vec<Joint> {
+        self.reference().joints()
+    }
+}
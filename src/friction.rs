@@ -0,0 +1,41 @@
+/// Angular velocity scale, in rad/s, over which Coulomb friction ramps
+/// up to its full magnitude. Keeps the friction torque continuous so
+/// integrators don't chatter around zero velocity.
+const COULOMB_RAMP: f64 = 1e-3;
+
+/// Energy loss model for a single pendulum joint.
+///
+/// Combines three resisting torques, each with its own coefficient.
+/// All coefficients default to zero, i.e. a frictionless joint.
+///
+/// # Fields
+/// * `viscous` - Viscous damping, torque proportional to the joint's rate (N·m·s/rad).
+/// * `coulomb` - Coulomb (dry) friction, constant torque opposing the joint's rate (N·m).
+/// * `drag` - Quadratic air drag, torque proportional to the link's rate squared (N·m·s²/rad²).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Friction {
+    pub viscous: f64,
+    pub coulomb: f64,
+    pub drag: f64,
+}
+
+impl Friction {
+    /// Computes the torque exerted by the joint itself.
+    ///
+    /// Combines viscous damping and Coulomb friction, both of
+    /// which oppose the relative rate between the two sides of the joint.
+    ///
+    /// # Arguments
+    /// * `rate` - The relative angular velocity across the joint.
+    pub fn joint_torque(&self, rate: f64) -> f64 {
+        -self.viscous * rate - self.coulomb * (rate / COULOMB_RAMP).tanh()
+    }
+
+    /// Computes the torque exerted by air drag on the link driven by the joint.
+    ///
+    /// # Arguments
+    /// * `omega` - The absolute angular velocity of the link.
+    pub fn drag_torque(&self, omega: f64) -> f64 {
+        -self.drag * omega * omega.abs()
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    color::Color,
+    diagnostics::Diagnostics,
+    draw::{Draw, Rect, Style, Text, TextBaseline},
+    window::WindowCtx,
+};
+
+/// Font of the heads-up display text.
+const HUD_FONT: &str = "14px monospace";
+
+/// Width of one character of the heads-up display text in pixels.
+const HUD_CHAR_WIDTH: f64 = 8.4;
+
+/// Line height of the heads-up display text in pixels.
+const HUD_LINE_HEIGHT: f64 = 18.0;
+
+/// Margin between the heads-up display and the canvas edges in pixels.
+const HUD_MARGIN: f64 = 12.0;
+
+/// Padding between the heads-up display panel and its text in pixels.
+const HUD_PADDING: f64 = 6.0;
+
+/// Background color of the heads-up display panel.
+const HUD_BACKGROUND: Color = Color::BLACK.alpha(0.5);
+
+/// A heads-up display drawable listing a simulation's diagnostics.
+///
+/// Draws one line per quantity on a translucent panel in the top-left
+/// corner of the canvas, including the Lyapunov exponent if tracked.
+/// The text is filled using the current fill style of the canvas context.
+///
+/// # Fields
+/// * `0` - The diagnostics to display.
+pub struct Hud<'d>(pub &'d dyn Diagnostics);
+
+impl Draw for Hud<'_> {
+    /// Draws the diagnostics as lines of monospace text.
+    fn draw(&self, window: &WindowCtx) {
+        let Self(diagnostics) = self;
+        let mut lines = vec![
+            format!("KE    {:>10.4} J", diagnostics.kinetic_energy()),
+            format!("PE    {:>10.4} J", diagnostics.potential_energy()),
+            format!("E     {:>10.4} J", diagnostics.total_energy()),
+            format!("L     {:>10.4} kg m²/s", diagnostics.angular_momentum()),
+            format!("drift {:>10.2e}", diagnostics.energy_drift()),
+        ];
+        if let Some(exponent) = diagnostics.lyapunov_exponent() {
+            lines.push(format!("λ     {exponent:>10.4} 1/s"));
+        }
+
+        // Panel
+        let columns = lines.iter().map(|line| line.chars().count()).max();
+        let size = (
+            HUD_CHAR_WIDTH * columns.unwrap_or(0) as f64 + 2.0 * HUD_PADDING,
+            HUD_LINE_HEIGHT * lines.len() as f64 + 2.0 * HUD_PADDING,
+        );
+        window.draw(
+            &Rect((HUD_MARGIN, HUD_MARGIN), size)
+                .styled()
+                .fill(HUD_BACKGROUND.into()),
+        );
+
+        // Text
+        for (i, line) in lines.into_iter().enumerate() {
+            let x = HUD_MARGIN + HUD_PADDING;
+            let y = HUD_MARGIN + HUD_PADDING + HUD_LINE_HEIGHT * i as f64;
+            window.draw(
+                &Text((x, y), line)
+                    .styled()
+                    .font(HUD_FONT.into())
+                    .text_baseline(TextBaseline::Top),
+            );
+        }
+    }
+}
+
+impl Style for Hud<'_> {}
@@ -0,0 +1,207 @@
+use std::f64::consts::{PI, TAU};
+
+/// Distance in meters from a bob within which a pointer grabs it.
+pub const GRAB_RADIUS: f64 = 0.1;
+
+/// Number of passes the inverse kinematics solver makes over the links.
+const IK_ITERATIONS: usize = 10;
+
+/// Weight of the newest measurement in the smoothed throw velocity.
+const THROW_SMOOTHING: f64 = 0.5;
+
+/// Time in seconds after the last pointer move beyond which a
+/// release counts as letting go of a still bob rather than a throw.
+const THROW_WINDOW: f64 = 0.1;
+
+/// Phase of a pointer interaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointerPhase {
+    Down,
+    Move,
+    Up,
+}
+
+/// A mouse, touch or pen event on the canvas.
+///
+/// # Fields
+/// * `phase` - Whether the pointer was pressed, moved or released.
+/// * `id` - Identifies the pointer, so multiple touches can be told apart.
+/// * `x` - Horizontal position in CSS pixels from the canvas' left edge.
+/// * `y` - Vertical position in CSS pixels from the canvas' top edge.
+/// * `ts` - Timestamp in seconds.
+#[derive(Clone, Copy, Debug)]
+pub struct PointerEvent {
+    pub phase: PointerPhase,
+    pub id: i32,
+    pub x: f64,
+    pub y: f64,
+    pub ts: f64,
+}
+
+/// A mouse wheel event on the canvas.
+///
+/// # Fields
+/// * `x` - Horizontal position in CSS pixels from the canvas' left edge.
+/// * `y` - Vertical position in CSS pixels from the canvas' top edge.
+/// * `delta` - Vertical scroll distance in pixels, positive when scrolling down.
+#[derive(Clone, Copy, Debug)]
+pub struct WheelEvent {
+    pub x: f64,
+    pub y: f64,
+    pub delta: f64,
+}
+
+/// A bob currently held by a pointer.
+struct Grab {
+    pointer: i32,
+    bob: usize,
+    thetas: Vec<f64>,
+    omegas: Vec<f64>,
+    ts: f64,
+}
+
+/// Lets a pointer drag the bobs of a pendulum around.
+///
+/// Works on any pendulum whose state is laid out as absolute
+/// `(theta, omega)` pairs per link. While a bob is held, the
+/// links up to it follow the pointer by inverse kinematics and
+/// the simulation should stop integrating.
+#[derive(Default)]
+pub struct Dragger {
+    throw: bool,
+    grab: Option<Grab>,
+}
+
+impl Dragger {
+    /// Creates a new Dragger.
+    ///
+    /// # Arguments
+    /// * `throw` - Whether released bobs keep the velocity they were dragged
+    ///   with, rather than being let go from rest.
+    pub fn new(throw: bool) -> Self {
+        Self { throw, grab: None }
+    }
+
+    /// Returns whether a bob is currently held.
+    pub fn is_dragging(&self) -> bool {
+        self.grab.is_some()
+    }
+
+    /// Applies a pointer event to the pendulum's state.
+    ///
+    /// Returns whether a held bob was released.
+    ///
+    /// # Arguments
+    /// * `event` - The pointer event.
+    /// * `target` - The event's position in meters relative to the pivot, `y` down.
+    /// * `lengths` - Length of each link, from the pivot outward.
+    /// * `state` - The pendulum's `(theta_1, omega_1, ..., theta_n, omega_n)`.
+    pub fn handle(
+        &mut self,
+        event: &PointerEvent,
+        target: (f64, f64),
+        lengths: &[f64],
+        state: &mut [f64],
+    ) -> bool {
+        match (event.phase, &mut self.grab) {
+            // Grab the nearest bob in reach
+            (PointerPhase::Down, None) => {
+                let nearest = bob_positions(lengths, state)
+                    .into_iter()
+                    .map(|(x, y)| (x - target.0).hypot(y - target.1))
+                    .enumerate()
+                    .filter(|&(_, distance)| distance <= GRAB_RADIUS)
+                    .min_by(|(_, a), (_, b)| a.total_cmp(b));
+                if let Some((bob, _)) = nearest {
+                    for omega in state.iter_mut().skip(1).step_by(2) {
+                        *omega = 0.0;
+                    }
+                    self.grab = Some(Grab {
+                        pointer: event.id,
+                        bob,
+                        thetas: thetas(state),
+                        omegas: vec![0.0; lengths.len()],
+                        ts: event.ts,
+                    });
+                }
+                false
+            }
+
+            // Follow the pointer, estimating the angular velocities
+            (PointerPhase::Move, Some(grab)) if grab.pointer == event.id => {
+                reach(lengths, state, grab.bob, target);
+                let new = thetas(state);
+                let dt = event.ts - grab.ts;
+                if dt > 0.0 {
+                    for (omega, (new, old)) in
+                        grab.omegas.iter_mut().zip(new.iter().zip(&grab.thetas))
+                    {
+                        *omega += THROW_SMOOTHING * ((new - old) / dt - *omega);
+                    }
+                    grab.thetas = new;
+                    grab.ts = event.ts;
+                }
+                false
+            }
+
+            // Let go, throwing if the pointer was still moving
+            (PointerPhase::Up, Some(grab)) if grab.pointer == event.id => {
+                if self.throw && event.ts - grab.ts < THROW_WINDOW {
+                    for (i, omega) in grab.omegas.iter().enumerate() {
+                        state[2 * i + 1] = *omega;
+                    }
+                }
+                self.grab = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns the angle of every link.
+fn thetas(state: &[f64]) -> Vec<f64> {
+    state.iter().step_by(2).copied().collect()
+}
+
+/// Computes every bob's position in meters relative to the pivot.
+fn bob_positions(lengths: &[f64], state: &[f64]) -> Vec<(f64, f64)> {
+    let (mut x, mut y) = (0.0, 0.0);
+    lengths
+        .iter()
+        .enumerate()
+        .map(|(i, length)| {
+            let (s, c) = state[2 * i].sin_cos();
+            x += length * s;
+            y += length * c;
+            (x, y)
+        })
+        .collect()
+}
+
+/// Moves a bob towards the target by cyclic coordinate descent,
+/// rotating the links from the pivot up to that bob.
+///
+/// # Arguments
+/// * `lengths` - Length of each link, from the pivot outward.
+/// * `state` - The pendulum's `(theta_1, omega_1, ..., theta_n, omega_n)`.
+/// * `bob` - Index of the bob to move.
+/// * `target` - Target position in meters relative to the pivot.
+fn reach(lengths: &[f64], state: &mut [f64], bob: usize, target: (f64, f64)) {
+    for _ in 0..IK_ITERATIONS {
+        for i in (0..=bob).rev() {
+            // Joint and bob positions
+            let nodes = bob_positions(lengths, state);
+            let (x_0, y_0) = if i == 0 { (0.0, 0.0) } else { nodes[i - 1] };
+            let (x_1, y_1) = nodes[bob];
+
+            // Rotate the links past the joint to point the bob at the target
+            let angle = |x: f64, y: f64| (x - x_0).atan2(y - y_0);
+            let delta = angle(target.0, target.1) - angle(x_1, y_1);
+            let delta = (delta + PI).rem_euclid(TAU) - PI;
+            for j in i..=bob {
+                state[2 * j] += delta;
+            }
+        }
+    }
+}
@@ -0,0 +1,385 @@
+/// A system of first-order ordinary differential equations.
+///
+/// Implementors supply the time derivative of a state vector. States are
+/// laid out as interleaved `(position, velocity)` pairs, i.e.
+/// `(theta_1, omega_1, theta_2, omega_2, ...)`, so that symplectic schemes
+/// can tell generalized coordinates apart from their velocities.
+pub trait Dynamics {
+    /// Writes the time derivative of `state` into `out`.
+    ///
+    /// # Arguments
+    /// * `state` - The current state vector.
+    /// * `out` - Buffer of the same length receiving `dstate/dt`.
+    fn derivative(&self, state: &[f64], out: &mut [f64]);
+}
+
+/// Number of accepted and rejected steps taken by an integrator.
+///
+/// Fixed-step schemes always take exactly one accepted step, while
+/// adaptive schemes may subdivide or retry a step to meet their tolerance.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StepStats {
+    pub accepted: u32,
+    pub rejected: u32,
+}
+
+impl StepStats {
+    /// Stats for a single accepted step.
+    pub const SINGLE: Self = Self {
+        accepted: 1,
+        rejected: 0,
+    };
+}
+
+impl std::ops::AddAssign for StepStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.accepted += rhs.accepted;
+        self.rejected += rhs.rejected;
+    }
+}
+
+/// Trait for numerical schemes that advance a `Dynamics` system in time.
+///
+/// Simulations hold a boxed integrator and only provide their derivative
+/// function, so schemes can be swapped without touching the physics.
+pub trait Integrator {
+    /// Advances `state` in place by a time step of `dt` seconds.
+    ///
+    /// # Arguments
+    /// * `dynamics` - The system supplying the derivative function.
+    /// * `state` - The state vector to advance.
+    /// * `dt` - The time step in seconds.
+    ///
+    /// # Returns
+    /// The number of accepted and rejected internal steps.
+    fn step(&mut self, dynamics: &dyn Dynamics, state: &mut [f64], dt: f64) -> StepStats;
+}
+
+impl<I: Integrator + ?Sized> Integrator for Box<I> {
+    fn step(&mut self, dynamics: &dyn Dynamics, state: &mut [f64], dt: f64) -> StepStats {
+        (**self).step(dynamics, state, dt)
+    }
+}
+
+/// Names accepted by `by_name`, one per integrator.
+pub const NAMES: [&str; 6] = [
+    "euler",
+    "semi-implicit-euler",
+    "velocity-verlet",
+    "rk4",
+    "rk45",
+    "rk45-adaptive",
+];
+
+/// Builds an integrator from its name.
+///
+/// Lets integrators be chosen from configuration such as command line
+/// arguments. The tolerances only apply to the adaptive integrator.
+///
+/// # Arguments
+/// * `name` - One of `NAMES`.
+/// * `atol` - Absolute error tolerance per component.
+/// * `rtol` - Relative error tolerance per component.
+///
+/// # Returns
+/// The integrator, or `None` if the name is unknown or the
+/// adaptive integrator's tolerances are not positive and finite.
+pub fn by_name(name: &str, atol: f64, rtol: f64) -> Option<Box<dyn Integrator>> {
+    match name {
+        "euler" => Some(Box::new(Euler)),
+        "semi-implicit-euler" => Some(Box::new(SemiImplicitEuler)),
+        "velocity-verlet" => Some(Box::new(VelocityVerlet)),
+        "rk4" => Some(Box::new(Rk4)),
+        "rk45" => Some(Box::new(DormandPrince)),
+        "rk45-adaptive" => {
+            AdaptiveDormandPrince::new(atol, rtol).map(|i| Box::new(i) as Box<dyn Integrator>)
+        }
+        _ => None,
+    }
+}
+
+/// Evaluates the stages of an explicit Runge-Kutta method.
+///
+/// Given the lower-triangular coefficients `a` of a Butcher tableau, returns
+/// the slope `k_i` of every stage. Callers combine them with their own weights.
+fn rk_stages(dynamics: &dyn Dynamics, state: &[f64], dt: f64, a: &[&[f64]]) -> Vec<Vec<f64>> {
+    let mut ks: Vec<Vec<f64>> = Vec::with_capacity(a.len());
+    let mut y = vec![0.0; state.len()];
+    for row in a {
+        // Intermediate state from the previous slopes
+        for (i, yi) in y.iter_mut().enumerate() {
+            *yi = state[i] + dt * row.iter().zip(&ks).map(|(a, k)| a * k[i]).sum::<f64>();
+        }
+
+        // Slope at intermediate state
+        let mut k = vec![0.0; state.len()];
+        dynamics.derivative(&y, &mut k);
+        ks.push(k);
+    }
+    ks
+}
+
+/// Combines stage slopes with weights `b` and adds them onto `state`.
+fn rk_combine(state: &mut [f64], dt: f64, ks: &[Vec<f64>], b: &[f64]) {
+    for (i, yi) in state.iter_mut().enumerate() {
+        *yi += dt * b.iter().zip(ks).map(|(b, k)| b * k[i]).sum::<f64>();
+    }
+}
+
+/// Explicit (forward) Euler method.
+///
+/// First order and not energy-conserving. Mostly useful as a baseline.
+pub struct Euler;
+
+impl Integrator for Euler {
+    fn step(&mut self, dynamics: &dyn Dynamics, state: &mut [f64], dt: f64) -> StepStats {
+        let ks = rk_stages(dynamics, state, dt, &[&[]]);
+        rk_combine(state, dt, &ks, &[1.0]);
+        StepStats::SINGLE
+    }
+}
+
+/// Semi-implicit (symplectic) Euler method.
+///
+/// Updates velocities first, then positions using the new velocities.
+/// First order, but keeps the energy error bounded for conservative systems.
+pub struct SemiImplicitEuler;
+
+impl Integrator for SemiImplicitEuler {
+    fn step(&mut self, dynamics: &dyn Dynamics, state: &mut [f64], dt: f64) -> StepStats {
+        let mut k = vec![0.0; state.len()];
+
+        // Velocities from current accelerations
+        dynamics.derivative(state, &mut k);
+        for i in (1..state.len()).step_by(2) {
+            state[i] += k[i] * dt;
+        }
+
+        // Positions from updated velocities
+        dynamics.derivative(state, &mut k);
+        for i in (0..state.len()).step_by(2) {
+            state[i] += k[i] * dt;
+        }
+        StepStats::SINGLE
+    }
+}
+
+/// Velocity Verlet method.
+///
+/// Second order and symplectic for position-dependent forces. For
+/// velocity-dependent accelerations, the end-of-step acceleration is
+/// evaluated at an Euler-predicted velocity.
+pub struct VelocityVerlet;
+
+impl Integrator for VelocityVerlet {
+    fn step(&mut self, dynamics: &dyn Dynamics, state: &mut [f64], dt: f64) -> StepStats {
+        let mut k_0 = vec![0.0; state.len()];
+        let mut k_1 = vec![0.0; state.len()];
+
+        // Positions from current velocity and acceleration
+        dynamics.derivative(state, &mut k_0);
+        let mut next = state.to_vec();
+        for i in (0..state.len()).step_by(2) {
+            next[i] += k_0[i] * dt + 0.5 * k_0[i + 1] * dt * dt;
+            next[i + 1] += k_0[i + 1] * dt;
+        }
+
+        // Velocities from averaged accelerations
+        dynamics.derivative(&next, &mut k_1);
+        for i in (0..state.len()).step_by(2) {
+            state[i] = next[i];
+            state[i + 1] += 0.5 * (k_0[i + 1] + k_1[i + 1]) * dt;
+        }
+        StepStats::SINGLE
+    }
+}
+
+/// Classical fourth-order Runge-Kutta method.
+pub struct Rk4;
+
+impl Integrator for Rk4 {
+    fn step(&mut self, dynamics: &dyn Dynamics, state: &mut [f64], dt: f64) -> StepStats {
+        let ks = rk_stages(
+            dynamics,
+            state,
+            dt,
+            &[&[], &[0.5], &[0.0, 0.5], &[0.0, 0.0, 1.0]],
+        );
+        rk_combine(
+            state,
+            dt,
+            &ks,
+            &[1.0 / 6.0, 1.0 / 3.0, 1.0 / 3.0, 1.0 / 6.0],
+        );
+        StepStats::SINGLE
+    }
+}
+
+/// Dormand-Prince coefficients of the stage slopes.
+const DOPRI_A: [&[f64]; 7] = [
+    &[],
+    &[1.0 / 5.0],
+    &[3.0 / 40.0, 9.0 / 40.0],
+    &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+    &[
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+    ],
+    &[
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+    ],
+    &[
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+
+/// Dormand-Prince fifth-order solution weights.
+const DOPRI_B: [f64; 7] = [
+    35.0 / 384.0,
+    0.0,
+    500.0 / 1113.0,
+    125.0 / 192.0,
+    -2187.0 / 6784.0,
+    11.0 / 84.0,
+    0.0,
+];
+
+/// Dormand-Prince RK45 method.
+///
+/// Advances with the fifth-order solution of the embedded 5(4) pair
+/// using the full frame step.
+pub struct DormandPrince;
+
+impl Integrator for DormandPrince {
+    fn step(&mut self, dynamics: &dyn Dynamics, state: &mut [f64], dt: f64) -> StepStats {
+        let ks = rk_stages(dynamics, state, dt, &DOPRI_A);
+        rk_combine(state, dt, &ks, &DOPRI_B);
+        StepStats::SINGLE
+    }
+}
+
+/// Dormand-Prince error weights, the difference between
+/// the fifth- and fourth-order solution weights.
+const DOPRI_E: [f64; 7] = [
+    35.0 / 384.0 - 5179.0 / 57600.0,
+    0.0,
+    500.0 / 1113.0 - 7571.0 / 16695.0,
+    125.0 / 192.0 - 393.0 / 640.0,
+    -2187.0 / 6784.0 + 92097.0 / 339200.0,
+    11.0 / 84.0 - 187.0 / 2100.0,
+    -1.0 / 40.0,
+];
+
+/// Safety factor applied to the optimal step size estimate.
+const SAFETY: f64 = 0.9;
+
+/// Bounds on how much the step size may change after a single attempt.
+const MIN_SCALE: f64 = 0.2;
+const MAX_SCALE: f64 = 5.0;
+
+/// Step size below which steps are accepted regardless of error,
+/// so that the integrator cannot stall on a singularity.
+const MIN_STEP: f64 = 1e-12;
+
+/// Adaptive Dormand-Prince RK45 method.
+///
+/// Covers each frame step with as many internal steps as needed to keep the
+/// embedded error estimate within `atol + rtol * |y|` for every component.
+/// Near-singular configurations get small steps while calm motion gets large
+/// ones. The step size carries over between calls.
+pub struct AdaptiveDormandPrince {
+    atol: f64,
+    rtol: f64,
+    h: Option<f64>,
+}
+
+impl AdaptiveDormandPrince {
+    /// Creates a new adaptive integrator with the given tolerances,
+    /// or `None` unless both are positive and finite.
+    ///
+    /// # Arguments
+    /// * `atol` - Absolute error tolerance per component.
+    /// * `rtol` - Relative error tolerance per component.
+    pub fn new(atol: f64, rtol: f64) -> Option<Self> {
+        let valid = |tol: f64| tol > 0.0 && tol.is_finite();
+        (valid(atol) && valid(rtol)).then_some(Self {
+            atol,
+            rtol,
+            h: None,
+        })
+    }
+
+    /// Computes the RMS of the error estimate scaled by the tolerance.
+    ///
+    /// Values at or below one satisfy the tolerance.
+    fn error_norm(&self, state: &[f64], next: &[f64], ks: &[Vec<f64>], h: f64) -> f64 {
+        let sum = (0..state.len())
+            .map(|i| {
+                let err = h * DOPRI_E.iter().zip(ks).map(|(e, k)| e * k[i]).sum::<f64>();
+                let scale = self.atol + self.rtol * state[i].abs().max(next[i].abs());
+                (err / scale).powi(2)
+            })
+            .sum::<f64>();
+        (sum / state.len() as f64).sqrt()
+    }
+}
+
+impl Integrator for AdaptiveDormandPrince {
+    fn step(&mut self, dynamics: &dyn Dynamics, state: &mut [f64], dt: f64) -> StepStats {
+        let mut stats = StepStats::default();
+        let mut h = self.h.unwrap_or(dt);
+        let mut t = 0.0;
+        while t < dt {
+            // Attempt a step, truncated to the end of the frame step
+            let h_try = h.min(dt - t);
+            let ks = rk_stages(dynamics, state, h_try, &DOPRI_A);
+            let mut next = state.to_vec();
+            rk_combine(&mut next, h_try, &ks, &DOPRI_B);
+            let err = self.error_norm(state, &next, &ks, h_try);
+
+            // A non-finite error means the dynamics have blown up,
+            // which smaller steps can't recover from, so take the
+            // step as is, like a fixed-step method would
+            if !err.is_finite() {
+                state.copy_from_slice(&next);
+                stats.accepted += 1;
+                break;
+            }
+
+            // Accept or reject the step
+            if err <= 1.0 || h_try <= MIN_STEP {
+                state.copy_from_slice(&next);
+                t += h_try;
+                stats.accepted += 1;
+            } else {
+                stats.rejected += 1;
+            }
+
+            // Propose next step size, keeping the proposal
+            // if a truncated step was accepted
+            let scale = if err == 0.0 {
+                MAX_SCALE
+            } else {
+                (SAFETY * err.powf(-0.2)).clamp(MIN_SCALE, MAX_SCALE)
+            };
+            h = if err <= 1.0 && h_try < h {
+                h.max(h_try * scale)
+            } else {
+                h_try * scale
+            };
+        }
+        self.h = Some(h);
+        stats
+    }
+}
@@ -0,0 +1,125 @@
+use crate::integrator::{Dynamics, Integrator, Rk4};
+
+/// Default initial distance between the reference and shadow trajectories.
+const DEFAULT_SEPARATION: f64 = 1e-8;
+
+/// Default time between renormalizations, in seconds.
+const DEFAULT_INTERVAL: f64 = 0.1;
+
+/// Online estimator of the maximal Lyapunov exponent.
+///
+/// Uses the Benettin method: evolves a shadow copy of the system from a
+/// slightly perturbed state alongside the reference trajectory, and every
+/// `interval` seconds measures how far the two have separated, accumulates
+/// the logarithm of the growth, and pulls the shadow back to the initial
+/// separation along the direction it has drifted in. The running average
+/// growth rate converges to the maximal Lyapunov exponent, positive for
+/// chaotic motion and near zero for regular motion.
+///
+/// Separation is measured as the Euclidean distance between state vectors.
+/// For an accurate estimate, the shadow should be integrated with the same
+/// integrator and time step as the reference.
+pub struct Lyapunov {
+    separation: f64,
+    interval: f64,
+    integrator: Box<dyn Integrator>,
+    shadow: Vec<f64>,
+    elapsed: f64,
+    time: f64,
+    log_growth: f64,
+}
+
+impl Default for Lyapunov {
+    /// Separation of `1e-8`, renormalized every 0.1s, integrated with `Rk4`.
+    fn default() -> Self {
+        Self {
+            separation: DEFAULT_SEPARATION,
+            interval: DEFAULT_INTERVAL,
+            integrator: Box::new(Rk4),
+            shadow: Vec::new(),
+            elapsed: 0.0,
+            time: 0.0,
+            log_growth: 0.0,
+        }
+    }
+}
+
+impl Lyapunov {
+    /// Sets the initial distance between the reference and shadow trajectories.
+    pub fn separation(self, separation: f64) -> Self {
+        Self { separation, ..self }
+    }
+
+    /// Sets the time between renormalizations, in seconds.
+    pub fn interval(self, interval: f64) -> Self {
+        Self { interval, ..self }
+    }
+
+    /// Sets the integrator used to advance the shadow trajectory.
+    pub fn integrator(self, integrator: impl Integrator + 'static) -> Self {
+        Self {
+            integrator: Box::new(integrator),
+            ..self
+        }
+    }
+
+    /// Restarts the estimate from the given reference state, placing
+    /// the shadow at the initial separation, equally along every axis.
+    pub fn reset(&mut self, state: &[f64]) {
+        let offset = self.separation / (state.len() as f64).sqrt();
+        self.shadow = state.iter().map(|x| x + offset).collect();
+        self.elapsed = 0.0;
+        self.time = 0.0;
+        self.log_growth = 0.0;
+    }
+
+    /// Advances the shadow trajectory by one time step, renormalizing
+    /// if an interval has passed.
+    ///
+    /// # Arguments
+    /// * `dynamics` - The system supplying the derivative function.
+    /// * `state` - The reference state, already advanced by the same time step.
+    /// * `dt` - The time step in seconds.
+    pub fn step(&mut self, dynamics: &dyn Dynamics, state: &[f64], dt: f64) {
+        if self.shadow.len() != state.len() {
+            self.reset(state);
+            return;
+        }
+        self.integrator.step(dynamics, &mut self.shadow, dt);
+        self.elapsed += dt;
+        if self.elapsed < self.interval {
+            return;
+        }
+
+        // Renormalize
+        let distance = self
+            .shadow
+            .iter()
+            .zip(state)
+            .map(|(s, x)| (s - x) * (s - x))
+            .sum::<f64>()
+            .sqrt();
+        if !(distance.is_finite() && distance > 0.0) {
+            self.reset(state);
+            return;
+        }
+        self.log_growth += (distance / self.separation).ln();
+        self.time += self.elapsed;
+        self.elapsed = 0.0;
+        let scale = self.separation / distance;
+        for (s, x) in self.shadow.iter_mut().zip(state) {
+            *s = x + (*s - x) * scale;
+        }
+    }
+
+    /// Returns the estimated maximal Lyapunov exponent in 1/s,
+    /// or `None` before the first renormalization.
+    pub fn exponent(&self) -> Option<f64> {
+        (self.time > 0.0).then(|| self.log_growth / self.time)
+    }
+
+    /// Returns the time in seconds the current estimate is averaged over.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+}
@@ -1,27 +1,148 @@
+#[cfg(feature = "web")]
 use crate::{
-    consts::{GRAVITY, METERS_TO_PIXELS},
-    draw::{FilledCircle, Line, Style},
-    sim::{RenderCtx, Simulation, UpdateCtx},
+    camera::Bounds,
+    color::Color,
+    consts::ROD_WIDTH,
+    draw::{FilledCircle, Line, LineCap, Style},
+    sim::{InputCtx, RenderCtx},
 };
+use crate::{
+    consts::GRAVITY,
+    diagnostics::Diagnostics,
+    friction::Friction,
+    input::Dragger,
+    integrator::{Dynamics, Integrator, Rk4},
+    record::{Joint, Observe},
+    sim::{Simulation, UpdateCtx},
+    trail::Trail,
+};
+
+/// State vector of a pendulum, laid out as `(theta, omega)`.
+pub type State = [f64; 2];
+
+/// Physical parameters of a pendulum.
+///
+/// Supplies the equations of motion to the integrator
+/// through its `Dynamics` implementation.
+#[derive(Clone, Copy)]
+pub struct PendulumParams {
+    pub length: f64,
+    pub mass: f64,
+    pub friction: Friction,
+}
+
+impl Default for PendulumParams {
+    /// A frictionless 1m pendulum with a 1kg bob.
+    fn default() -> Self {
+        Self {
+            length: 1.0,
+            mass: 1.0,
+            friction: Friction::default(),
+        }
+    }
+}
+
+impl PendulumParams {
+    /// Kinetic energy of the bob in the given state.
+    pub fn kinetic_energy(&self, state: &State) -> f64 {
+        let [_, omega] = *state;
+        0.5 * self.mass * self.length * self.length * omega * omega
+    }
+
+    /// Potential energy of the bob in the given state,
+    /// zero at the pivot height.
+    pub fn potential_energy(&self, state: &State) -> f64 {
+        let [theta, _] = *state;
+        -self.mass * GRAVITY * self.length * theta.cos()
+    }
+
+    /// Angular momentum of the bob about the pivot in the given state.
+    pub fn angular_momentum(&self, state: &State) -> f64 {
+        let [_, omega] = *state;
+        self.mass * self.length * self.length * omega
+    }
+}
+
+impl Dynamics for PendulumParams {
+    /// Computes the time derivative of the given state.
+    ///
+    /// dtheta/dt = omega
+    /// domega/dt = -(g / L) sin(theta) + (joint torque + drag torque) / (m L²)
+    ///
+    /// where the joint torque combines viscous and Coulomb friction,
+    /// and the drag torque is quadratic air drag, both from `friction`.
+    fn derivative(&self, state: &[f64], out: &mut [f64]) {
+        let &[theta, omega] = state else {
+            panic!("pendulum state must have 2 components");
+        };
+        let gravity = -theta.sin() * GRAVITY / self.length;
+        let torque = self.friction.joint_torque(omega) + self.friction.drag_torque(omega);
+        let dampening = torque / (self.mass * self.length * self.length);
+        out.copy_from_slice(&[omega, gravity + dampening]);
+    }
+}
 
 /// Represents a single pendulum in the simulation.
 ///
 /// The pendulum has a fixed length and damping coefficient, and
 /// tracks its current angle (theta) and angular velocity (omega).
 pub struct Pendulum {
-    pub length: f64,
-    theta: f64,
-    omega: f64,
+    pub params: PendulumParams,
+    state: State,
+    prev: State,
+    integrator: Box<dyn Integrator>,
+    initial_energy: f64,
+    drag: Dragger,
+    trail: Option<Trail>,
 }
 
 impl Pendulum {
-    /// Creates a new Pendulum with the specified length,
-    /// damping, initial angle, and initial angular velocity.
-    pub fn new(length: f64, theta_init: f64, omega_init: f64) -> Self {
+    /// Creates a new Pendulum with the specified physical
+    /// parameters and initial state.
+    ///
+    /// Integrates with `Rk4` unless configured otherwise.
+    ///
+    /// # Arguments
+    /// * `params` - Rod length, bob mass and joint friction.
+    /// * `state` - Initial `(theta, omega)`.
+    pub fn new(params: PendulumParams, state: State) -> Self {
+        Self {
+            params,
+            state,
+            prev: state,
+            integrator: Box::new(Rk4),
+            initial_energy: params.kinetic_energy(&state) + params.potential_energy(&state),
+            drag: Dragger::default(),
+            trail: None,
+        }
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Sets the integrator used to advance the simulation.
+    pub fn integrator(self, integrator: impl Integrator + 'static) -> Self {
+        Self {
+            integrator: Box::new(integrator),
+            ..self
+        }
+    }
+
+    /// Sets whether a dragged bob keeps its velocity when released.
+    pub fn throw(self, throw: bool) -> Self {
         Self {
-            length,
-            theta: theta_init,
-            omega: omega_init,
+            drag: Dragger::new(throw),
+            ..self
+        }
+    }
+
+    /// Sets a trail to draw behind the bobs.
+    pub fn trail(self, trail: Trail) -> Self {
+        Self {
+            trail: Some(trail),
+            ..self
         }
     }
 }
@@ -30,34 +151,127 @@ impl Simulation for Pendulum {
     /// Renders the pendulum on the provided render context.
     ///
     /// Draws the pivot point, the rod, and the bob of the pendulum on the canvas.
+    #[cfg(feature = "web")]
     fn render(&self, render: &RenderCtx) {
+        let [theta, _] = render.interpolate(&self.prev, &self.state);
+
         // Pivot
-        let x0 = (render.window.canvas.width() as f64) / 2.0;
-        let y0 = (render.window.canvas.height() as f64) * (1.0 / 4.0);
+        let (x0, y0) = render.camera.to_screen((0.0, 0.0));
 
         // End
-        let (s, c) = self.theta.sin_cos();
-        let x1 = x0 + self.length * METERS_TO_PIXELS * s;
-        let y1 = y0 + self.length * METERS_TO_PIXELS * c;
+        let (s, c) = theta.sin_cos();
+        let (x1, y1) = render
+            .camera
+            .to_screen((self.params.length * s, self.params.length * c));
+
+        // Sizes
+        let pivot_radius = render.camera.to_pixels(0.025);
+        let bob_radius = render.camera.to_pixels(0.05);
+        let rod_width = render.camera.to_pixels(ROD_WIDTH);
 
         // Render
         render.clear();
-        render.draw(&Line(x0, y0, x1, y1).styled().stroke("#ffff00".into()));
-        render.draw(&FilledCircle((x0, y0), 5.0).styled().fill("#ffffff".into()));
-        render.draw(&FilledCircle((x1, y1), 10.0).styled().fill("#00aaff".into()));
+        if let Some(trail) = &self.trail {
+            trail.render(render);
+        }
+        render.draw(
+            &Line(x0, y0, x1, y1)
+                .styled()
+                .stroke(Color::hex(0xffff00).into())
+                .line_width(rod_width)
+                .line_cap(LineCap::Round),
+        );
+        render.draw(
+            &FilledCircle((x0, y0), pivot_radius)
+                .styled()
+                .fill(Color::WHITE.into()),
+        );
+        render.draw(
+            &FilledCircle((x1, y1), bob_radius)
+                .styled()
+                .fill(Color::hex(0x00aaff).into())
+                .shadow_blur(bob_radius)
+                .shadow_color(Color::hex(0x00aaff)),
+        );
     }
 
-    /// Updates the pendulum's state based on the update context.
+    /// Lets the bob be dragged around with the pointer.
     ///
-    /// Applies the physics equations to update
-    /// the angle and angular velocity for the next frame.
+    /// Energy is measured from the moment the bob is released.
+    #[cfg(feature = "web")]
+    fn input(&mut self, input: &InputCtx) {
+        for event in input.events {
+            let target = input.to_world(event);
+            if self
+                .drag
+                .handle(event, target, &[self.params.length], &mut self.state)
+            {
+                self.initial_energy = self.total_energy();
+            }
+        }
+        if self.drag.is_dragging() {
+            input.claim();
+        }
+    }
+
+    /// The pendulum can reach a rod length from the pivot.
+    #[cfg(feature = "web")]
+    fn bounds(&self) -> Option<Bounds> {
+        Some(Bounds::around_pivot(self.params.length))
+    }
+
+    /// Updates the pendulum's state based on the update context.
     ///
-    /// dtheta/dt = omega
-    /// domega/dt = -(gravity contribution) - dampening
+    /// Advances the state by one fixed step using the configured integrator,
+    /// and reports the integrator's step counts to the context.
+    /// Holds still while the bob is being dragged.
     fn update(&mut self, update: &UpdateCtx) {
-        let dt = update.frame.dt;
-        let gravity = -self.theta.sin() * GRAVITY / self.length;
-        self.theta += self.omega * dt;
-        self.omega += gravity * dt;
+        self.prev = self.state;
+        if self.drag.is_dragging() {
+            return;
+        }
+        let stats = self
+            .integrator
+            .step(&self.params, &mut self.state, update.dt);
+        update.record(stats);
+        if let Some(mut trail) = self.trail.take() {
+            trail.push(&self.joints());
+            self.trail = Some(trail);
+        }
+    }
+
+    fn diagnostics(&self) -> Option<&dyn Diagnostics> {
+        Some(self)
+    }
+}
+
+impl Diagnostics for Pendulum {
+    fn kinetic_energy(&self) -> f64 {
+        self.params.kinetic_energy(&self.state)
+    }
+
+    fn potential_energy(&self) -> f64 {
+        self.params.potential_energy(&self.state)
+    }
+
+    fn initial_energy(&self) -> f64 {
+        self.initial_energy
+    }
+
+    fn angular_momentum(&self) -> f64 {
+        self.params.angular_momentum(&self.state)
+    }
+}
+
+impl Observe for Pendulum {
+    fn joints(&self) -> Vec<Joint> {
+        let [theta, omega] = self.state;
+        let (s, c) = theta.sin_cos();
+        vec![Joint {
+            theta,
+            omega,
+            x: self.params.length * s,
+            y: self.params.length * c,
+        }]
     }
 }
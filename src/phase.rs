@@ -0,0 +1,268 @@
+use std::{collections::VecDeque, f64::consts::PI};
+
+use crate::record::Joint;
+#[cfg(feature = "web")]
+use crate::{
+    color::Color,
+    draw::{Line, LineJoin, Polyline, Rect, Style, Text, TextAlign, TextBaseline},
+    sim::RenderCtx,
+};
+
+/// Default number of points kept per joint, i.e. ten
+/// seconds of simulation at 240 steps per second.
+const DEFAULT_LENGTH: usize = 2400;
+
+/// Default inset, as the origin and size in fractions of the canvas,
+/// in the bottom-right corner.
+const DEFAULT_VIEWPORT: ((f64, f64), (f64, f64)) = ((0.66, 0.62), (0.32, 0.36));
+
+/// Colors cycled through for the joints, from the pivot outward,
+/// matching the bobs of the pendulums.
+#[cfg(feature = "web")]
+const JOINT_COLORS: [Color; 4] = [
+    Color::hex(0x00ff00),
+    Color::hex(0x00aaff),
+    Color::hex(0xff4444),
+    Color::hex(0xaa88ff),
+];
+
+/// Background color of the plot panel.
+#[cfg(feature = "web")]
+const BACKGROUND: Color = Color::BLACK.alpha(0.6);
+
+/// Color of the axes and their labels.
+#[cfg(feature = "web")]
+const AXIS_COLOR: Color = Color::hex(0x888888);
+
+/// Padding between the panel edges and the plotted region in pixels.
+#[cfg(feature = "web")]
+const PADDING: f64 = 16.0;
+
+/// Scrolling phase-space plot of each joint's `(theta, omega)`.
+///
+/// Keeps a ring buffer of recent points per plotted joint and draws them as
+/// lines on an inset panel, with `theta` on the horizontal axis and `omega`
+/// on the vertical axis. Axes scale to fit the points, or span `[-π, π]`
+/// for `theta` when wrapping angles. Plots every joint unless configured
+/// otherwise.
+///
+/// A regular pendulum traces closed orbits, while a chaotic one
+/// wanders and fills out a region of the plane.
+#[derive(Clone)]
+pub struct PhasePlot {
+    length: usize,
+    wrap: bool,
+    #[cfg_attr(not(feature = "web"), allow(dead_code))]
+    viewport: ((f64, f64), (f64, f64)),
+    joints: Option<Vec<usize>>,
+    paths: Vec<VecDeque<(f64, f64)>>,
+}
+
+impl Default for PhasePlot {
+    /// An unwrapped plot of every joint in the bottom-right corner.
+    fn default() -> Self {
+        Self {
+            length: DEFAULT_LENGTH,
+            wrap: false,
+            viewport: DEFAULT_VIEWPORT,
+            joints: None,
+            paths: Vec::new(),
+        }
+    }
+}
+
+impl PhasePlot {
+    /// Sets the number of points kept per joint.
+    pub fn length(self, length: usize) -> Self {
+        Self { length, ..self }
+    }
+
+    /// Sets whether angles are wrapped to `[-π, π]`.
+    pub fn wrap(self, wrap: bool) -> Self {
+        Self { wrap, ..self }
+    }
+
+    /// Sets the inset the plot is drawn in.
+    ///
+    /// # Arguments
+    /// * `origin` - The top-left corner, as fractions of the canvas size.
+    /// * `size` - The width and height, as fractions of the canvas size.
+    pub fn viewport(self, origin: (f64, f64), size: (f64, f64)) -> Self {
+        Self {
+            viewport: (origin, size),
+            ..self
+        }
+    }
+
+    /// Sets which joints to plot, by index from the pivot outward.
+    pub fn joints(self, joints: Vec<usize>) -> Self {
+        Self {
+            joints: Some(joints),
+            ..self
+        }
+    }
+
+    /// Adds the current point of every plotted joint, dropping the
+    /// oldest points once the plot is at its full length.
+    ///
+    /// # Arguments
+    /// * `joints` - The pendulum's joints, from the pivot outward.
+    pub fn push(&mut self, joints: &[Joint]) {
+        let plotted = match &self.joints {
+            Some(plotted) => plotted.clone(),
+            None => (0..joints.len()).collect(),
+        };
+        self.paths.resize_with(plotted.len(), VecDeque::new);
+        for (path, i) in self.paths.iter_mut().zip(plotted) {
+            let Some(joint) = joints.get(i) else {
+                continue;
+            };
+            if path.len() >= self.length {
+                path.pop_front();
+            }
+            if self.length > 0 {
+                let theta = if self.wrap {
+                    wrap_angle(joint.theta)
+                } else {
+                    joint.theta
+                };
+                path.push_back((theta, joint.omega));
+            }
+        }
+    }
+
+    /// Forgets all points.
+    pub fn clear(&mut self) {
+        self.paths.clear();
+    }
+
+    /// Returns the plotted points of each joint as `(theta, omega)`, oldest first.
+    pub fn paths(&self) -> &[VecDeque<(f64, f64)>] {
+        &self.paths
+    }
+
+    /// Renders the plot in its inset of the canvas.
+    ///
+    /// Draws a panel with the `theta = 0` and `omega = 0` axes, then each
+    /// joint's points as a line, broken where a wrapped angle jumps
+    /// between `-π` and `π`.
+    ///
+    /// # Arguments
+    /// * `render` - The render context.
+    #[cfg(feature = "web")]
+    pub fn render(&self, render: &RenderCtx) {
+        let points = || self.paths.iter().flatten();
+        let theta_max = if self.wrap {
+            PI
+        } else {
+            points().fold(0.0, |max: f64, &(theta, _)| max.max(theta.abs()))
+        };
+        let omega_max = points().fold(0.0, |max: f64, &(_, omega)| max.max(omega.abs()));
+        let axes = Axes::draw(render, self.viewport, (theta_max, omega_max), ("θ", "ω"));
+
+        // Trajectories
+        for (i, path) in self.paths.iter().enumerate() {
+            let color = JOINT_COLORS[i % JOINT_COLORS.len()];
+            let mut segment = Vec::new();
+            let mut previous: Option<f64> = None;
+            for &(theta, omega) in path {
+                if self.wrap && previous.is_some_and(|previous| (theta - previous).abs() > PI) {
+                    draw_segment(render, std::mem::take(&mut segment), color);
+                }
+                segment.push(axes.to_screen((theta, omega)));
+                previous = Some(theta);
+            }
+            draw_segment(render, segment, color);
+        }
+    }
+}
+
+/// Inset panel with axes through its center, mapping plot
+/// coordinates to screen space.
+///
+/// Both axes are symmetric about zero, so the origin stays centered.
+#[cfg(feature = "web")]
+pub struct Axes {
+    center: (f64, f64),
+    scale: (f64, f64),
+}
+
+#[cfg(feature = "web")]
+impl Axes {
+    /// Draws the panel and its labelled axes, and returns the
+    /// mapping for plotting on it.
+    ///
+    /// # Arguments
+    /// * `render` - The render context.
+    /// * `viewport` - The origin and size of the panel, as fractions of the canvas size.
+    /// * `extent` - The largest absolute horizontal and vertical values that must fit.
+    /// * `labels` - The names of the horizontal and vertical axes.
+    pub fn draw(
+        render: &RenderCtx,
+        viewport: ((f64, f64), (f64, f64)),
+        extent: (f64, f64),
+        labels: (&str, &str),
+    ) -> Self {
+        let (width, height) = render.window.size();
+        let ((u, v), (w, h)) = viewport;
+        let (x_0, y_0, w, h) = (u * width, v * height, w * width, h * height);
+        render.draw(&Rect((x_0, y_0), (w, h)).styled().fill(BACKGROUND.into()));
+        let axes = Self {
+            center: (x_0 + w / 2.0, y_0 + h / 2.0),
+            scale: (
+                (w / 2.0 - PADDING) / extent.0.max(f64::EPSILON),
+                (h / 2.0 - PADDING) / extent.1.max(f64::EPSILON),
+            ),
+        };
+
+        // Axes
+        let (x, y) = axes.center;
+        let axis = |line: Line| line.styled().stroke(AXIS_COLOR.into()).line_width(1.0);
+        render.draw(&axis(Line(x_0, y, x_0 + w, y)));
+        render.draw(&axis(Line(x, y_0, x, y_0 + h)));
+        let label = |text: Text, align| {
+            text.styled()
+                .fill(AXIS_COLOR.into())
+                .font("12px sans-serif".into())
+                .text_align(align)
+                .text_baseline(TextBaseline::Top)
+        };
+        render.draw(&label(
+            Text((x_0 + w - 4.0, y + 2.0), labels.0.into()),
+            TextAlign::Right,
+        ));
+        render.draw(&label(
+            Text((x + 4.0, y_0 + 2.0), labels.1.into()),
+            TextAlign::Left,
+        ));
+        axes
+    }
+
+    /// Converts a point from plot coordinates to screen space.
+    pub fn to_screen(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            self.center.0 + x * self.scale.0,
+            self.center.1 - y * self.scale.1,
+        )
+    }
+}
+
+/// Draws one unbroken part of a joint's trajectory.
+#[cfg(feature = "web")]
+fn draw_segment(render: &RenderCtx, points: Vec<(f64, f64)>, color: Color) {
+    if points.len() < 2 {
+        return;
+    }
+    render.draw(
+        &Polyline(points)
+            .styled()
+            .stroke(color.into())
+            .line_width(1.0)
+            .line_join(LineJoin::Round),
+    );
+}
+
+/// Wraps an angle in radians to `[-π, π)`.
+pub fn wrap_angle(theta: f64) -> f64 {
+    (theta + PI).rem_euclid(2.0 * PI) - PI
+}
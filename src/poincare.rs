@@ -0,0 +1,303 @@
+use std::fmt::Write as _;
+
+#[cfg(feature = "web")]
+use crate::{
+    color::Colormap,
+    draw::{Rect, Style},
+    phase::Axes,
+    sim::RenderCtx,
+};
+use crate::{
+    consts::GRAVITY,
+    double_pendulum::{DoublePendulumParams, State},
+    integrator::Dynamics,
+    phase::wrap_angle,
+    record::{Format, json_number},
+};
+#[cfg(feature = "web")]
+use {wasm_bindgen::JsValue, web_sys::Blob};
+
+/// Largest departure from the section's energy of an observed state,
+/// as a fraction of the pendulum's potential energy well depth.
+const ENERGY_TOLERANCE: f64 = 1e-3;
+
+/// Newton iterations refining each crossing time within its step.
+const NEWTON_ITERATIONS: usize = 8;
+
+/// Default inset, as the origin and size in fractions of the canvas,
+/// in the bottom-left corner.
+const DEFAULT_VIEWPORT: ((f64, f64), (f64, f64)) = ((0.02, 0.62), (0.32, 0.36));
+
+/// Side length of a plotted point in pixels.
+#[cfg(feature = "web")]
+const POINT_SIZE: f64 = 2.0;
+
+/// Hue step between the colors of consecutive orbits, the golden
+/// ratio conjugate, so any number of orbits get distinct colors.
+#[cfg(feature = "web")]
+const ORBIT_HUE_STEP: f64 = 0.618_033_988_749_895;
+
+/// A point where a trajectory pierced the Poincaré section.
+///
+/// # Fields
+/// * `orbit` - Index of the trajectory, counting from zero.
+/// * `t` - Simulated time of the crossing in seconds.
+/// * `theta_2` - Absolute angle of the second rod, wrapped to `[-π, π)`.
+/// * `omega_2` - Absolute angular velocity of the second rod.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Crossing {
+    pub orbit: usize,
+    pub t: f64,
+    pub theta_2: f64,
+    pub omega_2: f64,
+}
+
+/// Records a Poincaré section of double pendulum trajectories.
+///
+/// Records `(theta_2, omega_2)` every time the first rod swings through
+/// straight down counterclockwise on screen, i.e. `theta_1` crosses zero,
+/// modulo full turns, with `omega_1 > 0`. Crossings are located within
+/// each physics step by cubic Hermite interpolation of the state, using
+/// the equations of motion for its slopes.
+///
+/// Sections are taken at a fixed energy chosen at construction. Each
+/// trajectory started from `initial_state` adds an orbit to the section,
+/// tracing a closed curve on a KAM torus when motion is regular, or
+/// scattering points when chaotic. Raising the energy shows the tori
+/// breaking up. States off the section's energy, e.g. after a bob was
+/// dragged or once friction has drained the pendulum, end the orbit
+/// and are not recorded, so the pendulum should be frictionless.
+pub struct PoincareSection {
+    params: DoublePendulumParams,
+    energy: f64,
+    orbit: usize,
+    previous: Option<(f64, State)>,
+    crossings: Vec<Crossing>,
+    #[cfg_attr(not(feature = "web"), allow(dead_code))]
+    viewport: ((f64, f64), (f64, f64)),
+}
+
+impl PoincareSection {
+    /// Creates a new empty PoincareSection.
+    ///
+    /// # Arguments
+    /// * `params` - Parameters of the sectioned double pendulum.
+    /// * `energy` - Total energy of the trajectories, in joules.
+    pub fn new(params: DoublePendulumParams, energy: f64) -> Self {
+        Self {
+            params,
+            energy,
+            orbit: 0,
+            previous: None,
+            crossings: Vec::new(),
+            viewport: DEFAULT_VIEWPORT,
+        }
+    }
+
+    /// Sets the inset the section is drawn in.
+    ///
+    /// # Arguments
+    /// * `origin` - The top-left corner, as fractions of the canvas size.
+    /// * `size` - The width and height, as fractions of the canvas size.
+    pub fn viewport(self, origin: (f64, f64), size: (f64, f64)) -> Self {
+        Self {
+            viewport: (origin, size),
+            ..self
+        }
+    }
+
+    /// Returns the parameters of the sectioned double pendulum.
+    pub fn params(&self) -> &DoublePendulumParams {
+        &self.params
+    }
+
+    /// Returns the energy of the section, in joules.
+    pub fn energy(&self) -> f64 {
+        self.energy
+    }
+
+    /// Returns a starting state on the section at its energy,
+    /// or `None` if the energy can't be reached from there.
+    ///
+    /// # Arguments
+    /// * `theta_2` - Absolute angle of the second rod.
+    /// * `omega_2` - Absolute angular velocity of the second rod.
+    pub fn initial_state(&self, theta_2: f64, omega_2: f64) -> Option<State> {
+        self.params.state_at_energy(self.energy, theta_2, omega_2)
+    }
+
+    /// Starts recording a new orbit, e.g. after restarting the
+    /// simulation from another initial state.
+    pub fn next_orbit(&mut self) {
+        if self.previous.take().is_some() {
+            self.orbit += 1;
+        }
+    }
+
+    /// Observes the state after a physics step, recording a crossing
+    /// if the trajectory pierced the section since the last state.
+    ///
+    /// # Arguments
+    /// * `t` - Simulated time in seconds.
+    /// * `state` - The state, in absolute angles.
+    pub fn observe(&mut self, t: f64, state: State) {
+        if !self.on_energy(&state) {
+            self.next_orbit();
+            return;
+        }
+        if let Some((t_0, previous)) = self.previous.replace((t, state))
+            && let Some(crossing) = self.crossing(t_0, &previous, t, &state)
+        {
+            self.crossings.push(crossing);
+        }
+    }
+
+    /// Returns whether the state lies at the section's energy,
+    /// up to the drift of numerical integration.
+    fn on_energy(&self, state: &State) -> bool {
+        let params = &self.params;
+        let energy = params.kinetic_energy(state) + params.potential_energy(state);
+        let depth = (params.mass_1 + params.mass_2) * GRAVITY * params.length_1
+            + params.mass_2 * GRAVITY * params.length_2;
+        (energy - self.energy).abs() <= ENERGY_TOLERANCE * depth
+    }
+
+    /// Locates a crossing of the section between two consecutive states.
+    fn crossing(&self, t_0: f64, start: &State, t_1: f64, end: &State) -> Option<Crossing> {
+        // Distance of theta_1 from the nearest multiple of a full turn
+        let theta_0 = wrap_angle(start[0]);
+        let theta_1 = theta_0 + (end[0] - start[0]);
+        if !(theta_0 < 0.0 && theta_1 >= 0.0) {
+            return None;
+        }
+
+        // Slopes of every component at both ends
+        let mut slope_0 = [0.0; 4];
+        let mut slope_1 = [0.0; 4];
+        self.params.derivative(start, &mut slope_0);
+        self.params.derivative(end, &mut slope_1);
+        let h = t_1 - t_0;
+        let spline = |i: usize| {
+            let (y_0, y_1) = if i == 0 {
+                (theta_0, theta_1)
+            } else {
+                (start[i], end[i])
+            };
+            Hermite([y_0, h * slope_0[i], y_1, h * slope_1[i]])
+        };
+
+        // Refine the crossing from the linear estimate
+        let theta = spline(0);
+        let mut s = -theta_0 / (theta_1 - theta_0);
+        for _ in 0..NEWTON_ITERATIONS {
+            let slope = theta.slope(s);
+            if slope == 0.0 {
+                break;
+            }
+            s = (s - theta.value(s) / slope).clamp(0.0, 1.0);
+        }
+
+        (spline(1).value(s) > 0.0).then(|| Crossing {
+            orbit: self.orbit,
+            t: t_0 + s * h,
+            theta_2: wrap_angle(spline(2).value(s)),
+            omega_2: spline(3).value(s),
+        })
+    }
+
+    /// Returns the recorded crossings, oldest first.
+    pub fn crossings(&self) -> &[Crossing] {
+        &self.crossings
+    }
+
+    /// Discards all recorded crossings and starts again from the first orbit.
+    pub fn clear(&mut self) {
+        self.crossings.clear();
+        self.previous = None;
+        self.orbit = 0;
+    }
+
+    /// Serializes all recorded crossings in the given format.
+    pub fn serialize(&self, format: Format) -> String {
+        let mut out = String::new();
+        if format == Format::Csv {
+            out += "orbit,t,theta_2,omega_2\n";
+        }
+        for c in &self.crossings {
+            match format {
+                Format::Csv => writeln!(out, "{},{},{},{}", c.orbit, c.t, c.theta_2, c.omega_2),
+                Format::JsonLines => writeln!(
+                    out,
+                    r#"{{"orbit":{},"t":{},"theta_2":{},"omega_2":{}}}"#,
+                    c.orbit,
+                    json_number(c.t),
+                    json_number(c.theta_2),
+                    json_number(c.omega_2),
+                ),
+            }
+            .unwrap();
+        }
+        out
+    }
+
+    /// Serializes all recorded crossings into a Blob that
+    /// JavaScript can offer as a download.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the Blob cannot be created.
+    #[cfg(feature = "web")]
+    pub fn to_blob(&self, format: Format) -> Result<Blob, JsValue> {
+        crate::record::blob(&self.serialize(format), format)
+    }
+
+    /// Renders the section in its inset of the canvas.
+    ///
+    /// Draws every crossing as a small square, colored by orbit.
+    ///
+    /// # Arguments
+    /// * `render` - The render context.
+    #[cfg(feature = "web")]
+    pub fn render(&self, render: &RenderCtx) {
+        let omega_max = self
+            .crossings
+            .iter()
+            .fold(0.0, |max: f64, c| max.max(c.omega_2.abs()));
+        let extent = (std::f64::consts::PI, omega_max);
+        let axes = Axes::draw(render, self.viewport, extent, ("θ₂", "ω₂"));
+        for c in &self.crossings {
+            let color = Colormap::Rainbow.sample((c.orbit as f64 * ORBIT_HUE_STEP).fract());
+            let (x, y) = axes.to_screen((c.theta_2, c.omega_2));
+            let half = POINT_SIZE / 2.0;
+            render.draw(
+                &Rect((x - half, y - half), (POINT_SIZE, POINT_SIZE))
+                    .styled()
+                    .fill(color.into()),
+            );
+        }
+    }
+}
+
+/// Cubic Hermite polynomial on `[0, 1]`, from the values and slopes
+/// `[y_0, m_0, y_1, m_1]` at both ends, with slopes scaled to the unit interval.
+struct Hermite([f64; 4]);
+
+impl Hermite {
+    /// Evaluates the polynomial at `s`, from 0 to 1.
+    fn value(&self, s: f64) -> f64 {
+        let [y_0, m_0, y_1, m_1] = self.0;
+        let (s_2, s_3) = (s * s, s * s * s);
+        (2.0 * s_3 - 3.0 * s_2 + 1.0) * y_0
+            + (s_3 - 2.0 * s_2 + s) * m_0
+            + (-2.0 * s_3 + 3.0 * s_2) * y_1
+            + (s_3 - s_2) * m_1
+    }
+
+    /// Evaluates the derivative of the polynomial at `s`, from 0 to 1.
+    fn slope(&self, s: f64) -> f64 {
+        let [y_0, m_0, y_1, m_1] = self.0;
+        let s_2 = s * s;
+        (6.0 * s_2 - 6.0 * s) * (y_0 - y_1)
+            + (3.0 * s_2 - 4.0 * s + 1.0) * m_0
+            + (3.0 * s_2 - 2.0 * s) * m_1
+    }
+}
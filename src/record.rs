@@ -0,0 +1,221 @@
+use std::{collections::VecDeque, fmt::Write as _, str::FromStr};
+
+use crate::diagnostics::Diagnostics;
+#[cfg(feature = "web")]
+use {
+    wasm_bindgen::JsValue,
+    web_sys::{Blob, BlobPropertyBag},
+};
+
+/// Kinematic state of a single joint and the bob at the end of its rod.
+///
+/// Positions are in meters relative to the pivot, with `y` pointing down.
+#[derive(Clone, Copy, Debug)]
+pub struct Joint {
+    pub theta: f64,
+    pub omega: f64,
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Trait for simulations whose joints can be observed.
+///
+/// Implementations list their joints from the pivot outward.
+pub trait Observe {
+    /// Returns the current state of every joint.
+    fn joints(&self) -> Vec<Joint>;
+}
+
+/// A single recorded sample of a simulation.
+#[derive(Clone, Debug)]
+pub struct Sample {
+    pub t: f64,
+    pub joints: Vec<Joint>,
+    pub energy: f64,
+}
+
+impl Sample {
+    /// Takes a sample of the simulation at the given time.
+    ///
+    /// # Arguments
+    /// * `t` - Simulated time in seconds.
+    /// * `sim` - The simulation to sample.
+    pub fn of<S: Observe + Diagnostics>(t: f64, sim: &S) -> Self {
+        Self {
+            t,
+            joints: sim.joints(),
+            energy: sim.total_energy(),
+        }
+    }
+
+    /// Returns the CSV header for samples with `joints` joints.
+    pub fn csv_header(joints: usize) -> String {
+        let mut header = String::from("t");
+        for i in 1..=joints {
+            write!(header, ",theta_{i},omega_{i},x_{i},y_{i}").unwrap();
+        }
+        header + ",energy"
+    }
+
+    /// Serializes the sample as a CSV row matching `csv_header`.
+    pub fn to_csv(&self) -> String {
+        let mut row = self.t.to_string();
+        for joint in &self.joints {
+            write!(
+                row,
+                ",{},{},{},{}",
+                joint.theta, joint.omega, joint.x, joint.y
+            )
+            .unwrap();
+        }
+        write!(row, ",{}", self.energy).unwrap();
+        row
+    }
+
+    /// Serializes the sample as a single-line JSON object.
+    pub fn to_json(&self) -> String {
+        let field = |get: fn(&Joint) -> f64| {
+            let values: Vec<String> = self.joints.iter().map(|j| json_number(get(j))).collect();
+            values.join(",")
+        };
+        format!(
+            r#"{{"t":{},"theta":[{}],"omega":[{}],"x":[{}],"y":[{}],"energy":{}}}"#,
+            json_number(self.t),
+            field(|j| j.theta),
+            field(|j| j.omega),
+            field(|j| j.x),
+            field(|j| j.y),
+            json_number(self.energy),
+        )
+    }
+}
+
+/// Formats a number for JSON, which has no representation
+/// for NaN or infinities, so those become `null`.
+pub fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".into()
+    }
+}
+
+/// File formats samples can be serialized to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Comma-separated values with a header row.
+    Csv,
+    /// One JSON object per line.
+    JsonLines,
+}
+
+impl Format {
+    /// MIME type of the format.
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::JsonLines => "application/jsonl",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    /// Parses `csv` or `jsonl`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "jsonl" => Ok(Self::JsonLines),
+            _ => Err(format!("unknown format '{s}', expected 'csv' or 'jsonl'")),
+        }
+    }
+}
+
+/// Records samples of a simulation's trajectory.
+///
+/// Keeps at most `capacity` of the most recent samples, so long
+/// runs in the browser don't grow without bound.
+pub struct Recorder {
+    samples: VecDeque<Sample>,
+    capacity: usize,
+}
+
+impl Recorder {
+    /// Creates a new empty Recorder.
+    ///
+    /// # Arguments
+    /// * `capacity` - Maximum number of samples kept.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Records a sample of the simulation at the given time,
+    /// dropping the oldest sample if at capacity. Records
+    /// nothing if the capacity is zero.
+    ///
+    /// # Arguments
+    /// * `t` - Simulated time in seconds.
+    /// * `sim` - The simulation to sample.
+    pub fn record<S: Observe + Diagnostics>(&mut self, t: f64, sim: &S) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample::of(t, sim));
+    }
+
+    /// Returns the recorded samples, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = &Sample> {
+        self.samples.iter()
+    }
+
+    /// Discards all recorded samples.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Serializes all recorded samples in the given format.
+    pub fn serialize(&self, format: Format) -> String {
+        let mut out = String::new();
+        if let (Format::Csv, Some(first)) = (format, self.samples.front()) {
+            out += &Sample::csv_header(first.joints.len());
+            out.push('\n');
+        }
+        for sample in &self.samples {
+            match format {
+                Format::Csv => out += &sample.to_csv(),
+                Format::JsonLines => out += &sample.to_json(),
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Serializes all recorded samples into a Blob that
+    /// JavaScript can offer as a download.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the Blob cannot be created.
+    #[cfg(feature = "web")]
+    pub fn to_blob(&self, format: Format) -> Result<Blob, JsValue> {
+        blob(&self.serialize(format), format)
+    }
+}
+
+/// Wraps serialized data in a Blob of the format's MIME type.
+///
+/// # Errors
+/// Returns a `JsValue` error if the Blob cannot be created.
+#[cfg(feature = "web")]
+pub fn blob(contents: &str, format: Format) -> Result<Blob, JsValue> {
+    let parts = js_sys::Array::of1(&JsValue::from(contents));
+    let options = BlobPropertyBag::new();
+    options.set_type(format.mime_type());
+    Blob::new_with_str_sequence_and_options(&parts, &options)
+}
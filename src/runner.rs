@@ -1,21 +1,54 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 use wasm_bindgen::JsValue;
 
 use crate::{
-    anim::{AnimationLoopRunner, FrameCtx},
-    sim::{RenderCtx, Simulation, UpdateCtx},
+    anim::{AnimationHandle, AnimationLoopRunner},
+    camera::Camera,
+    color::Color,
+    diagnostics::Diagnostics,
+    draw::Style,
+    hud::Hud,
+    integrator::StepStats,
+    phase::PhasePlot,
+    poincare::PoincareSection,
+    record::{Observe, Recorder},
+    sim::{FrameCtx, InputCtx, RenderCtx, Simulation, UpdateCtx},
     window::WindowCtx,
 };
 
+/// Default fixed physics time step, in seconds.
+const DEFAULT_STEP: f64 = 1.0 / 240.0;
+
+/// Default maximum number of physics steps taken in a single frame.
+const DEFAULT_MAX_SUBSTEPS: u32 = 10;
+
+/// Callback invoked after every physics step with the
+/// simulation and the simulated time in seconds.
+type StepHook<S> = Box<dyn FnMut(&S, f64)>;
+
 /// Runner for managing a simulation with rendering and updates in an animation loop.
 ///
 /// This struct coordinates between a simulation and the browser's animation frame
-/// callback, ensuring that the simulation is rendered and updated on each frame.
+/// callback. Physics advances in fixed time steps drawn from an accumulator of
+/// elapsed frame time, so results do not depend on the display's refresh rate.
 ///
 /// # Type Parameters
 /// * `S` - The simulation type that implements the `Simulation` trait.
 pub struct SimulationRunner<S: Simulation + 'static> {
     window: WindowCtx,
     sim: S,
+    camera: Camera,
+    step: f64,
+    max_substeps: u32,
+    accumulator: f64,
+    hud: bool,
+    phase_plot: Option<Rc<RefCell<PhasePlot>>>,
+    poincare: Option<Rc<RefCell<PoincareSection>>>,
+    time: f64,
+    on_step: Vec<StepHook<S>>,
 }
 
 impl<S: Simulation + 'static> SimulationRunner<S> {
@@ -25,29 +58,219 @@ impl<S: Simulation + 'static> SimulationRunner<S> {
     /// * `state` - The initial simulation state.
     /// * `window` - The window and canvas context for rendering.
     pub fn new(state: S, window: WindowCtx) -> Self {
-        Self { window, sim: state }
+        Self {
+            window,
+            sim: state,
+            camera: Camera::default(),
+            step: DEFAULT_STEP,
+            max_substeps: DEFAULT_MAX_SUBSTEPS,
+            accumulator: 0.0,
+            hud: false,
+            phase_plot: None,
+            poincare: None,
+            time: 0.0,
+            on_step: Vec::new(),
+        }
+    }
+
+    /// Sets the camera used to view the simulation.
+    pub fn camera(self, camera: Camera) -> Self {
+        Self { camera, ..self }
+    }
+
+    /// Sets the fixed physics time step, in seconds.
+    ///
+    /// # Errors
+    /// Returns a `JsValue` error if the step is not positive and finite.
+    pub fn step(self, step: f64) -> Result<Self, JsValue> {
+        if !(step > 0.0 && step.is_finite()) {
+            return Err(JsValue::from("Step must be positive and finite!"));
+        }
+        Ok(Self { step, ..self })
+    }
+
+    /// Sets the maximum number of physics steps taken in a single frame.
+    ///
+    /// Frame time beyond this budget (e.g. after the tab was in the
+    /// background) is dropped rather than simulated.
+    pub fn max_substeps(self, max_substeps: u32) -> Self {
+        Self {
+            max_substeps,
+            ..self
+        }
+    }
+
+    /// Sets whether to draw the simulation's diagnostics as a heads-up display.
+    ///
+    /// Has no effect for simulations that don't report diagnostics.
+    pub fn hud(self, hud: bool) -> Self {
+        Self { hud, ..self }
+    }
+
+    /// Adds a callback invoked after every physics step with
+    /// the simulation and the simulated time in seconds.
+    pub fn on_step(mut self, hook: impl FnMut(&S, f64) + 'static) -> Self {
+        self.on_step.push(Box::new(hook));
+        self
     }
 
     /// Starts the simulation loop.
     ///
     /// This method begins the animation loop using `requestAnimationFrame`.
-    /// On each frame, the simulation is rendered and then updated in sequence.
+    /// On each frame, the simulation is updated in fixed steps covering the
+    /// elapsed time, then rendered interpolated between the last two steps.
+    /// The returned handle pauses, steps or stops the loop.
     ///
     /// # Errors
     /// Returns a `JsValue` error if the animation frame request fails.
-    pub fn run(mut self) -> Result<(), JsValue> {
+    pub fn run(mut self) -> Result<AnimationHandle, JsValue> {
+        let (width, height) = self.window.size();
+        self.camera.set_viewport(width, height);
+
+        // Draw the initial state right away, so it shows
+        // even if the loop is paused before its first frame
+        if let (true, Some(bounds)) = (self.camera.is_auto_fit(), self.sim.bounds()) {
+            self.camera.fit(bounds);
+        }
+        let frame = FrameCtx {
+            frame: 0,
+            dt: 0.0,
+            ts: 0.0,
+        };
+        self.render(&frame, StepStats::default());
+
         AnimationLoopRunner::new(move |frame: &FrameCtx| {
-            // Render sim
-            let render = RenderCtx {
+            // Accumulate frame time, dropping what exceeds the substep budget
+            let budget = self.step * self.max_substeps as f64;
+            self.accumulator = (self.accumulator + frame.dt).min(budget);
+
+            // Re-layout when the canvas was resized
+            if let Some((width, height)) = self.window.update_size() {
+                self.camera.set_viewport(width, height);
+                self.sim.resize(width, height);
+            }
+
+            // Handle input, letting the camera have what the sim doesn't claim
+            let events = self.window.take_pointer_events();
+            let input = InputCtx {
                 window: &self.window,
                 frame,
+                camera: &self.camera,
+                events: &events,
+                claimed: Cell::new(false),
+            };
+            self.sim.input(&input);
+            if input.claimed.get() {
+                self.camera.cancel_pan();
+                self.camera.handle(&[], &self.window.take_wheel_events());
+            } else {
+                self.camera
+                    .handle(&events, &self.window.take_wheel_events());
+            }
+            if let (true, Some(bounds)) = (self.camera.is_auto_fit(), self.sim.bounds()) {
+                self.camera.fit(bounds);
+            }
+
+            // Update sim in fixed steps
+            let update = UpdateCtx {
+                frame,
+                dt: self.step,
+                steps: Cell::new(StepStats::default()),
             };
-            self.sim.render(&render);
+            while self.accumulator >= self.step {
+                self.sim.update(&update);
+                self.accumulator -= self.step;
+                self.time += self.step;
+                for on_step in &mut self.on_step {
+                    on_step(&self.sim, self.time);
+                }
+            }
 
-            // Update sim
-            let update = UpdateCtx { frame };
-            self.sim.update(&update);
+            self.render(frame, update.steps.get());
         })
         .run()
     }
+
+    /// Renders the simulation with its insets and heads-up display.
+    ///
+    /// # Arguments
+    /// * `frame` - The frame being rendered.
+    /// * `steps` - Integrator step counts from this frame's updates.
+    fn render(&self, frame: &FrameCtx, steps: StepStats) {
+        // Render sim
+        let render = RenderCtx {
+            window: &self.window,
+            frame,
+            camera: &self.camera,
+            alpha: self.accumulator / self.step,
+            steps,
+            diagnostics: self.sim.diagnostics(),
+        };
+        self.sim.render(&render);
+
+        // Render phase plot
+        if let Some(phase_plot) = &self.phase_plot {
+            phase_plot.borrow().render(&render);
+        }
+
+        // Render Poincaré section
+        if let Some(poincare) = &self.poincare {
+            poincare.borrow().render(&render);
+        }
+
+        // Render diagnostics
+        if let (true, Some(diagnostics)) = (self.hud, render.diagnostics) {
+            render.draw(&Hud(diagnostics).styled().fill(Color::WHITE.into()));
+        }
+    }
+}
+
+impl<S: Simulation + Observe + Diagnostics + 'static> SimulationRunner<S> {
+    /// Records the simulation's trajectory into the given recorder.
+    ///
+    /// Samples the initial state at t=0, then once after every physics step.
+    /// The recorder is shared so it can be read while the loop is running.
+    ///
+    /// # Arguments
+    /// * `recorder` - The recorder receiving the samples.
+    pub fn record(self, recorder: Rc<RefCell<Recorder>>) -> Self {
+        recorder.borrow_mut().record(self.time, &self.sim);
+        self.on_step(move |sim, t| recorder.borrow_mut().record(t, sim))
+    }
+}
+
+impl<S: Simulation + Observe + 'static> SimulationRunner<S> {
+    /// Draws a phase-space plot of the simulation's joints as an inset.
+    ///
+    /// Plots the initial state at t=0, then once after every physics step.
+    ///
+    /// # Arguments
+    /// * `phase_plot` - The phase plot, configured with its inset and joints.
+    pub fn phase_plot(mut self, mut phase_plot: PhasePlot) -> Self {
+        phase_plot.push(&self.sim.joints());
+        let phase_plot = Rc::new(RefCell::new(phase_plot));
+        self.phase_plot = Some(Rc::clone(&phase_plot));
+        self.on_step(move |sim, _| phase_plot.borrow_mut().push(&sim.joints()))
+    }
+
+    /// Records a Poincaré section of the simulation and draws it as an inset.
+    ///
+    /// Observes the initial state at t=0, then once after every physics step.
+    /// The section is shared so it can be read while the loop is running.
+    /// Only simulations with exactly two joints, i.e. double pendulums,
+    /// are sectioned.
+    ///
+    /// # Arguments
+    /// * `poincare` - The section receiving the states.
+    pub fn poincare(mut self, poincare: Rc<RefCell<PoincareSection>>) -> Self {
+        let observe = |poincare: &RefCell<PoincareSection>, t, sim: &S| {
+            if let [first, second] = sim.joints()[..] {
+                let state = [first.theta, first.omega, second.theta, second.omega];
+                poincare.borrow_mut().observe(t, state);
+            }
+        };
+        observe(&poincare, self.time, &self.sim);
+        self.poincare = Some(Rc::clone(&poincare));
+        self.on_step(move |sim, t| observe(&poincare, t, sim))
+    }
 }
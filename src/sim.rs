@@ -1,15 +1,44 @@
-use crate::{anim::FrameCtx, draw::Draw, window::WindowCtx};
+use std::cell::Cell;
+
+#[cfg(feature = "web")]
+use crate::{
+    camera::{Bounds, Camera},
+    draw::Draw,
+    input::PointerEvent,
+    window::WindowCtx,
+};
+use crate::{diagnostics::Diagnostics, integrator::StepStats};
+
+#[allow(dead_code)]
+/// Context for a single animation frame,
+/// containing the frame number, delta time, and timestamp.
+pub struct FrameCtx {
+    pub frame: u32,
+    pub dt: f64,
+    pub ts: f64,
+}
 
 /// Context for rendering a frame of the simulation.
 ///
 /// Provides access to the window and canvas context as well as frame-specific information.
 /// Passed to the `render` method of simulations to provide drawing capabilities.
+#[cfg(feature = "web")]
 #[allow(dead_code)]
 pub struct RenderCtx<'s> {
     pub window: &'s WindowCtx,
     pub frame: &'s FrameCtx,
+    /// Maps the simulation's world space in meters to the canvas.
+    pub camera: &'s Camera,
+    /// How far the frame lies between the previous and current
+    /// physics states, in `[0, 1)`.
+    pub alpha: f64,
+    /// Integrator step counts from this frame's updates.
+    pub steps: StepStats,
+    /// Diagnostics of the simulation being rendered, if it reports any.
+    pub diagnostics: Option<&'s dyn Diagnostics>,
 }
 
+#[cfg(feature = "web")]
 impl<'s> RenderCtx<'s> {
     /// Clears the canvas.
     ///
@@ -27,21 +56,99 @@ impl<'s> RenderCtx<'s> {
     pub fn draw(&self, d: &dyn Draw) {
         self.window.draw(d);
     }
+
+    /// Interpolates between the previous and current physics states.
+    ///
+    /// Blends each component by `alpha`, so rendering stays smooth when
+    /// the frame falls between two fixed physics steps.
+    ///
+    /// # Arguments
+    /// * `prev` - The state before the last physics step.
+    /// * `next` - The state after the last physics step.
+    pub fn interpolate<const N: usize>(&self, prev: &[f64; N], next: &[f64; N]) -> [f64; N] {
+        std::array::from_fn(|i| prev[i] + (next[i] - prev[i]) * self.alpha)
+    }
+
+    /// Interpolates between the previous and current physics states
+    /// of a simulation whose state length is only known at runtime.
+    ///
+    /// # Arguments
+    /// * `prev` - The state before the last physics step.
+    /// * `next` - The state after the last physics step.
+    pub fn interpolate_slice(&self, prev: &[f64], next: &[f64]) -> Vec<f64> {
+        prev.iter()
+            .zip(next)
+            .map(|(p, n)| p + (n - p) * self.alpha)
+            .collect()
+    }
 }
 
 /// Context for updating the simulation.
 ///
-/// Provides frame-specific information such as the current timestamp and delta time.
-/// Passed to the `update` method of simulations to allow time-based updates.
+/// Provides frame-specific information such as the current timestamp, as well as
+/// the fixed physics time step. Passed to the `update` method of simulations
+/// to allow time-based updates.
 #[allow(dead_code)]
 pub struct UpdateCtx<'s> {
     pub frame: &'s FrameCtx,
+    /// Fixed physics time step in seconds.
+    pub dt: f64,
+    /// Integrator step counts accumulated over this frame's updates.
+    pub steps: Cell<StepStats>,
+}
+
+impl<'s> UpdateCtx<'s> {
+    /// Records the step counts reported by an integrator.
+    ///
+    /// Counts accumulate across all updates within the same frame.
+    ///
+    /// # Arguments
+    /// * `stats` - The accepted and rejected step counts to add.
+    pub fn record(&self, stats: StepStats) {
+        let mut steps = self.steps.get();
+        steps += stats;
+        self.steps.set(steps);
+    }
+}
+
+/// Context for user input to the simulation.
+///
+/// Carries the pointer events received on the canvas since the last frame.
+/// Passed to the `input` method of simulations before each frame's updates.
+#[cfg(feature = "web")]
+pub struct InputCtx<'s> {
+    pub window: &'s WindowCtx,
+    pub frame: &'s FrameCtx,
+    /// Maps the simulation's world space in meters to the canvas.
+    pub camera: &'s Camera,
+    /// Pointer events since the last frame, oldest first.
+    pub events: &'s [PointerEvent],
+    /// Whether the simulation claimed this frame's pointer events.
+    pub claimed: Cell<bool>,
+}
+
+#[cfg(feature = "web")]
+impl<'s> InputCtx<'s> {
+    /// Converts a pointer event's position to world space in meters.
+    ///
+    /// # Arguments
+    /// * `event` - The pointer event.
+    pub fn to_world(&self, event: &PointerEvent) -> (f64, f64) {
+        self.camera.to_world((event.x, event.y))
+    }
+
+    /// Claims this frame's pointer events, e.g. while dragging
+    /// a bob, so they don't also pan the camera.
+    pub fn claim(&self) {
+        self.claimed.set(true);
+    }
 }
 
 /// Trait for simulation objects that can be rendered and updated.
 ///
 /// Implementations of this trait define how a simulation is rendered on each frame
-/// and how the simulation state is updated based on elapsed time.
+/// and how the simulation state is updated based on elapsed time. Rendering is
+/// only available with the `web` feature; updating works on any target.
 pub trait Simulation {
     /// Renders the current state of the simulation.
     ///
@@ -50,14 +157,53 @@ pub trait Simulation {
     ///
     /// # Arguments
     /// * `render` - The rendering context containing the window and frame information.
+    #[cfg(feature = "web")]
     fn render(&self, render: &RenderCtx);
 
     /// Updates the simulation state.
     ///
-    /// This method is called on each animation frame to update the simulation
-    /// based on the elapsed time since the last frame.
+    /// This method is called zero or more times per animation frame, advancing
+    /// the simulation by the fixed time step in `update.dt` each call.
     ///
     /// # Arguments
     /// * `update` - The update context containing frame timing information.
     fn update(&mut self, update: &UpdateCtx);
+
+    /// Handles user input.
+    ///
+    /// This method is called once per animation frame, before the
+    /// frame's updates. Does nothing unless overridden.
+    ///
+    /// # Arguments
+    /// * `input` - The input context containing the pointer events.
+    #[cfg(feature = "web")]
+    fn input(&mut self, input: &InputCtx) {
+        let _ = input;
+    }
+
+    /// Handles the canvas being resized, e.g. to re-layout anything
+    /// placed relative to the canvas. Does nothing unless overridden.
+    ///
+    /// # Arguments
+    /// * `width` - The new width in CSS pixels.
+    /// * `height` - The new height in CSS pixels.
+    #[cfg(feature = "web")]
+    fn resize(&mut self, width: f64, height: f64) {
+        let _ = (width, height);
+    }
+
+    /// Returns the region of world space the simulation can reach,
+    /// which the camera keeps fitted to the canvas, if known.
+    #[cfg(feature = "web")]
+    fn bounds(&self) -> Option<Bounds> {
+        None
+    }
+
+    /// Returns the simulation's diagnostics, if it reports any.
+    ///
+    /// Simulations implementing `Diagnostics` should return themselves,
+    /// which exposes their energies through the `RenderCtx`.
+    fn diagnostics(&self) -> Option<&dyn Diagnostics> {
+        None
+    }
 }
@@ -0,0 +1,168 @@
+use std::collections::VecDeque;
+
+use crate::{
+    color::{Color, Colormap},
+    record::Joint,
+};
+#[cfg(feature = "web")]
+use crate::{
+    draw::{Line, LineCap, Style},
+    sim::RenderCtx,
+};
+
+/// Default number of positions kept per bob, i.e. two
+/// seconds of simulation at 240 steps per second.
+const DEFAULT_LENGTH: usize = 480;
+
+/// Default line width of the newest part of the trail, in pixels.
+const DEFAULT_WIDTH: f64 = 3.0;
+
+/// Fading trail of recent bob positions.
+///
+/// Keeps a ring buffer of positions per traced bob, in meters relative
+/// to the pivot, and draws them as a line that thins out and fades
+/// with age. Traces the outermost bob unless configured otherwise.
+///
+/// Drawn in a single color, or colored by the speed of the bob
+/// at each position if given a colormap.
+#[derive(Clone)]
+pub struct Trail {
+    length: usize,
+    #[cfg_attr(not(feature = "web"), allow(dead_code))]
+    color: Color,
+    #[cfg_attr(not(feature = "web"), allow(dead_code))]
+    colormap: Option<(Colormap, f64)>,
+    #[cfg_attr(not(feature = "web"), allow(dead_code))]
+    width: f64,
+    bobs: Option<Vec<usize>>,
+    paths: Vec<VecDeque<(f64, f64, f64)>>,
+}
+
+impl Default for Trail {
+    /// A white trail of the outermost bob.
+    fn default() -> Self {
+        Self {
+            length: DEFAULT_LENGTH,
+            color: Color::WHITE,
+            colormap: None,
+            width: DEFAULT_WIDTH,
+            bobs: None,
+            paths: Vec::new(),
+        }
+    }
+}
+
+impl Trail {
+    /// Sets the number of positions kept per bob.
+    pub fn length(self, length: usize) -> Self {
+        Self { length, ..self }
+    }
+
+    /// Sets the color of the trail.
+    pub fn color(self, color: Color) -> Self {
+        Self { color, ..self }
+    }
+
+    /// Colors the trail by the speed of the bob instead of a single color.
+    ///
+    /// # Arguments
+    /// * `colormap` - The colormap, sampled from 0 at rest to 1 at `max_speed`.
+    /// * `max_speed` - The speed mapped to the end of the colormap, in m/s.
+    pub fn colormap(self, colormap: Colormap, max_speed: f64) -> Self {
+        Self {
+            colormap: Some((colormap, max_speed)),
+            ..self
+        }
+    }
+
+    /// Sets the line width of the newest part of the trail, in pixels.
+    pub fn width(self, width: f64) -> Self {
+        Self { width, ..self }
+    }
+
+    /// Sets which bobs to trace, by index from the pivot outward.
+    pub fn bobs(self, bobs: Vec<usize>) -> Self {
+        Self {
+            bobs: Some(bobs),
+            ..self
+        }
+    }
+
+    /// Adds the current bob positions, dropping the oldest
+    /// positions once the trail is at its full length.
+    ///
+    /// # Arguments
+    /// * `joints` - The pendulum's joints, from the pivot outward.
+    pub fn push(&mut self, joints: &[Joint]) {
+        let bobs = match &self.bobs {
+            Some(bobs) => bobs.clone(),
+            None => joints.len().checked_sub(1).into_iter().collect(),
+        };
+        self.paths.resize_with(bobs.len(), VecDeque::new);
+        for (path, bob) in self.paths.iter_mut().zip(bobs) {
+            let Some(joint) = joints.get(bob) else {
+                continue;
+            };
+            if path.len() >= self.length {
+                path.pop_front();
+            }
+            if self.length > 0 {
+                path.push_back((joint.x, joint.y, speed(&joints[..=bob])));
+            }
+        }
+    }
+
+    /// Forgets all positions.
+    pub fn clear(&mut self) {
+        self.paths.clear();
+    }
+
+    /// Renders the trail on the provided render context.
+    ///
+    /// Draws each path segment by segment, oldest first, scaling
+    /// both the opacity and the width of a segment by its age.
+    ///
+    /// # Arguments
+    /// * `render` - The render context.
+    #[cfg(feature = "web")]
+    pub fn render(&self, render: &RenderCtx) {
+        for path in &self.paths {
+            for (i, (&start, &end)) in path.iter().zip(path.iter().skip(1)).enumerate() {
+                let (x_0, y_0) = render.camera.to_screen((start.0, start.1));
+                let (x_1, y_1) = render.camera.to_screen((end.0, end.1));
+                let color = match self.colormap {
+                    Some((colormap, max_speed)) => colormap.sample(end.2 / max_speed),
+                    None => self.color,
+                };
+                let fade = 1.0 - (path.len() - 2 - i) as f64 / self.length as f64;
+                render.draw(
+                    &Line(x_0, y_0, x_1, y_1)
+                        .styled()
+                        .stroke(color.into())
+                        .line_width(self.width * fade)
+                        .line_cap(LineCap::Round)
+                        .global_alpha(fade),
+                );
+            }
+        }
+    }
+}
+
+/// Computes the speed of the outermost of the given joints, in m/s.
+///
+/// Each link rotating at `omega` moves the bobs below it
+/// perpendicular to the link, so the velocity sums over the links.
+///
+/// # Arguments
+/// * `joints` - The joints from the pivot down to the bob, with absolute angles.
+fn speed(joints: &[Joint]) -> f64 {
+    let mut previous = (0.0, 0.0);
+    let mut velocity = (0.0, 0.0);
+    for joint in joints {
+        let (dx, dy) = (joint.x - previous.0, joint.y - previous.1);
+        velocity.0 += joint.omega * dy;
+        velocity.1 -= joint.omega * dx;
+        previous = (joint.x, joint.y);
+    }
+    velocity.0.hypot(velocity.1)
+}
@@ -1,13 +1,52 @@
-use wasm_bindgen::{JsCast as _, JsValue};
-use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, window};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+use wasm_bindgen::{JsCast as _, JsValue, closure::Closure};
+use web_sys::{CanvasRenderingContext2d, Event, HtmlCanvasElement, window};
 
-use crate::draw::Draw;
+use crate::{
+    draw::Draw,
+    input::{PointerEvent, PointerPhase, WheelEvent},
+};
+
+const MILLIS_PER_SEC: f64 = 1000.0;
+
+/// Wheel scroll distance in pixels of one line, for
+/// browsers that report scrolling in lines.
+const WHEEL_LINE_HEIGHT: f64 = 16.0;
+
+/// Wheel scroll distance in pixels of one page, for
+/// browsers that report scrolling in pages.
+const WHEEL_PAGE_HEIGHT: f64 = 800.0;
+
+/// Browser pointer events listened to, and the phase each maps to.
+const POINTER_EVENTS: [(&str, PointerPhase); 4] = [
+    ("pointerdown", PointerPhase::Down),
+    ("pointermove", PointerPhase::Move),
+    ("pointerup", PointerPhase::Up),
+    ("pointercancel", PointerPhase::Up),
+];
+
+/// Event listener registered on the canvas.
+type Listener = Closure<dyn FnMut(Event)>;
 
 /// Context for the window and canvas,
 /// providing utilities for drawing on the canvas.
+///
+/// Also collects pointer and wheel events on the canvas until they are taken.
+///
+/// Drawing coordinates are in CSS pixels. The canvas backing store is kept
+/// at the displayed size times the device pixel ratio, so drawings stay
+/// sharp on high density screens.
 pub struct WindowCtx {
     pub ctx: CanvasRenderingContext2d,
     pub canvas: HtmlCanvasElement,
+    size: Cell<(f64, f64)>,
+    pixel_ratio: Cell<f64>,
+    pointer_events: Rc<RefCell<Vec<PointerEvent>>>,
+    wheel_events: Rc<RefCell<Vec<WheelEvent>>>,
+    listeners: Vec<(&'static str, Listener)>,
 }
 
 impl WindowCtx {
@@ -35,9 +74,87 @@ impl WindowCtx {
         Ok(window)
     }
 
-    /// Creates a new WindowCtx with the given canvas and rendering context.
+    /// Creates a new WindowCtx with the given canvas and rendering context,
+    /// and starts listening for pointer and wheel events on the canvas.
     pub fn new(canvas: HtmlCanvasElement, ctx: CanvasRenderingContext2d) -> Self {
-        Self { ctx, canvas }
+        let size = (canvas.width() as f64, canvas.height() as f64);
+        let mut window = Self {
+            ctx,
+            canvas,
+            size: Cell::new(size),
+            pixel_ratio: Cell::new(1.0),
+            pointer_events: Rc::new(RefCell::new(Vec::new())),
+            wheel_events: Rc::new(RefCell::new(Vec::new())),
+            listeners: Vec::new(),
+        };
+
+        // Pointer events
+        for (name, phase) in POINTER_EVENTS {
+            let canvas = window.canvas.clone();
+            let events = Rc::clone(&window.pointer_events);
+            window.listen(name, move |event| {
+                let event: &web_sys::PointerEvent = event.unchecked_ref();
+
+                // Keep receiving moves while dragging outside the canvas
+                if phase == PointerPhase::Down {
+                    canvas.set_pointer_capture(event.pointer_id()).unwrap();
+                }
+                events.borrow_mut().push(PointerEvent {
+                    phase,
+                    id: event.pointer_id(),
+                    x: event.offset_x() as f64,
+                    y: event.offset_y() as f64,
+                    ts: event.time_stamp() / MILLIS_PER_SEC,
+                });
+            });
+        }
+
+        // Wheel events
+        let events = Rc::clone(&window.wheel_events);
+        window.listen("wheel", move |event| {
+            let event: &web_sys::WheelEvent = event.unchecked_ref();
+            let unit = match event.delta_mode() {
+                web_sys::WheelEvent::DOM_DELTA_LINE => WHEEL_LINE_HEIGHT,
+                web_sys::WheelEvent::DOM_DELTA_PAGE => WHEEL_PAGE_HEIGHT,
+                _ => 1.0,
+            };
+            events.borrow_mut().push(WheelEvent {
+                x: event.offset_x() as f64,
+                y: event.offset_y() as f64,
+                delta: event.delta_y() * unit,
+            });
+        });
+
+        // Pin a canvas sized only by its attributes to its current size,
+        // or its displayed size would follow the enlarged backing store
+        let canvas = &window.canvas;
+        let displayed = (canvas.client_width() as u32, canvas.client_height() as u32);
+        if displayed == (canvas.width(), canvas.height()) {
+            let style = canvas.style();
+            style
+                .set_property("width", &format!("{}px", displayed.0))
+                .unwrap();
+            style
+                .set_property("height", &format!("{}px", displayed.1))
+                .unwrap();
+        }
+
+        // Backing store
+        window.update_size();
+        window
+    }
+
+    /// Registers a listener for the named event on the canvas,
+    /// suppressing the browser's default handling of the event.
+    fn listen(&mut self, name: &'static str, mut f: impl FnMut(&Event) + 'static) {
+        let listener = Listener::new(move |event: Event| {
+            f(&event);
+            event.prevent_default();
+        });
+        self.canvas
+            .add_event_listener_with_callback(name, listener.as_ref().unchecked_ref())
+            .unwrap();
+        self.listeners.push((name, listener));
     }
 
     /// Double-dispatch draw method
@@ -47,8 +164,69 @@ impl WindowCtx {
 
     /// Clears the entire canvas.
     pub fn clear(&self) {
-        let width = self.canvas.width() as f64;
-        let height = self.canvas.height() as f64;
+        let (width, height) = self.size();
         self.ctx.clear_rect(0.0, 0.0, width, height);
     }
+
+    /// Returns the displayed size of the canvas in CSS pixels.
+    pub fn size(&self) -> (f64, f64) {
+        self.size.get()
+    }
+
+    /// Returns the number of device pixels per CSS pixel.
+    pub fn pixel_ratio(&self) -> f64 {
+        self.pixel_ratio.get()
+    }
+
+    /// Matches the canvas backing store to the displayed size of
+    /// the canvas and the device pixel ratio, if either changed,
+    /// e.g. after the window was resized or the page zoomed.
+    ///
+    /// Returns the new size in CSS pixels if the canvas was resized.
+    pub fn update_size(&self) -> Option<(f64, f64)> {
+        let pixel_ratio = window().map_or(1.0, |window| window.device_pixel_ratio());
+        let size = (
+            self.canvas.client_width() as f64,
+            self.canvas.client_height() as f64,
+        );
+
+        // Skip if unchanged, or hidden and so without a size
+        let unchanged = size == self.size() && pixel_ratio == self.pixel_ratio();
+        if unchanged || size.0 <= 0.0 || size.1 <= 0.0 {
+            return None;
+        }
+
+        // Resize backing store, which also resets the transform
+        self.canvas.set_width((size.0 * pixel_ratio).round() as u32);
+        self.canvas
+            .set_height((size.1 * pixel_ratio).round() as u32);
+        self.ctx
+            .set_transform(pixel_ratio, 0.0, 0.0, pixel_ratio, 0.0, 0.0)
+            .unwrap();
+        self.size.set(size);
+        self.pixel_ratio.set(pixel_ratio);
+        Some(size)
+    }
+
+    /// Takes the pointer events received since the last call, oldest first.
+    pub fn take_pointer_events(&self) -> Vec<PointerEvent> {
+        self.pointer_events.take()
+    }
+
+    /// Takes the wheel events received since the last call, oldest first.
+    pub fn take_wheel_events(&self) -> Vec<WheelEvent> {
+        self.wheel_events.take()
+    }
+}
+
+impl Drop for WindowCtx {
+    /// Stops listening for events, since the
+    /// listeners can't be called once they are dropped.
+    fn drop(&mut self) {
+        for (name, listener) in &self.listeners {
+            let _ = self
+                .canvas
+                .remove_event_listener_with_callback(name, listener.as_ref().unchecked_ref());
+        }
+    }
 }
@@ -0,0 +1,154 @@
+//! CSS color parsing and formatting, HSL conversion and colormaps.
+
+use rust_wasm_double_pendulum::color::{Color, Colormap};
+
+/// Asserts that two colors match in every channel, up to rounding.
+#[track_caller]
+fn assert_close(a: Color, b: Color) {
+    let channels = |c: Color| [c.r, c.g, c.b, c.a];
+    for (x, y) in channels(a).into_iter().zip(channels(b)) {
+        assert!((x - y).abs() < 1e-12, "{a:?} != {b:?}");
+    }
+}
+
+/// Parses a color that must be valid.
+#[track_caller]
+fn parse(s: &str) -> Color {
+    s.parse().unwrap_or_else(|e| panic!("{e}"))
+}
+
+#[test]
+fn parses_hex_colors() {
+    assert_eq!(parse("#f80"), Color::rgb(255, 136, 0));
+    assert_eq!(parse("#f808"), Color::rgb(255, 136, 0).alpha(136.0 / 255.0));
+    assert_eq!(parse("#FF8800"), Color::hex(0xff8800));
+    assert_eq!(
+        parse("#ff880080"),
+        Color::hex(0xff8800).alpha(128.0 / 255.0)
+    );
+    assert_eq!(parse("  #000  "), Color::BLACK);
+}
+
+#[test]
+fn parses_functional_colors() {
+    // Comma, space and slash separators
+    assert_eq!(parse("rgb(255, 0, 128)"), Color::rgb(255, 0, 128));
+    assert_eq!(parse("rgb(255 0 128)"), Color::rgb(255, 0, 128));
+    assert_eq!(
+        parse("RGBA(255, 0, 0, 0.5)"),
+        Color::rgb(255, 0, 0).alpha(0.5)
+    );
+    assert_eq!(
+        parse("rgb(255 0 0 / 50%)"),
+        Color::rgb(255, 0, 0).alpha(0.5)
+    );
+
+    // Percentages, clamped to the valid range
+    assert_close(
+        parse("rgb(100%, 0%, 50%)"),
+        Color {
+            r: 1.0,
+            g: 0.0,
+            b: 0.5,
+            a: 1.0,
+        },
+    );
+    assert_eq!(parse("rgb(300, -5, 0)"), Color::rgb(255, 0, 0));
+
+    // Hues with and without units
+    assert_close(parse("hsl(120, 100%, 50%)"), Color::hex(0x00ff00));
+    assert_close(
+        parse("hsl(120deg 100% 50% / 0.25)"),
+        Color::hex(0x00ff00).alpha(0.25),
+    );
+    assert_close(parse("hsla(240, 100%, 50%, 1)"), Color::hex(0x0000ff));
+}
+
+#[test]
+fn rejects_invalid_colors() {
+    for s in [
+        "",
+        "red",
+        "#",
+        "#12",
+        "#12345",
+        "#1234567",
+        "#ggg",
+        "rgb(1, 2)",
+        "rgb(1, 2, 3, 4, 5)",
+        "rgb(1, 2, 3",
+        "rgb(a, b, c)",
+        "rgb(nan, 0, 0)",
+        "rgb(1, 2, 3, x)",
+        "cmyk(1, 2, 3)",
+        "hsl(x, 10%, 10%)",
+        "hsl(10, 10%, y%)",
+    ] {
+        assert!(s.parse::<Color>().is_err(), "parsed '{s}'");
+    }
+}
+
+#[test]
+fn display_round_trips() {
+    for color in [
+        Color::hex(0x123456),
+        Color::WHITE,
+        Color::TRANSPARENT,
+        Color::rgb(10, 20, 30).alpha(0.5),
+    ] {
+        assert_eq!(parse(&color.to_string()), color);
+    }
+    assert_eq!(Color::hex(0x00aaff).to_string(), "#00aaff");
+    assert_eq!(
+        Color::rgb(10, 20, 30).alpha(0.5).to_string(),
+        "rgba(10, 20, 30, 0.5)"
+    );
+}
+
+#[test]
+fn hsl_matches_known_colors() {
+    assert_close(Color::hsl(0.0, 1.0, 0.5), Color::hex(0xff0000));
+    assert_close(Color::hsl(60.0, 1.0, 0.5), Color::hex(0xffff00));
+    assert_close(Color::hsl(210.0, 0.5, 0.4), Color::hex(0x336699));
+    assert_close(Color::hsl(0.0, 0.0, 1.0), Color::WHITE);
+
+    // Hues wrap around a full turn
+    assert_close(Color::hsl(-120.0, 1.0, 0.5), Color::hex(0x0000ff));
+    assert_close(Color::hsl(480.0, 1.0, 0.5), Color::hex(0x00ff00));
+
+    let (h, s, l) = Color::hex(0x336699).to_hsl();
+    assert!((h - 210.0).abs() < 1e-9 && (s - 0.5).abs() < 1e-9 && (l - 0.4).abs() < 1e-9);
+}
+
+#[test]
+fn colormaps_span_their_endpoints() {
+    assert_eq!(Colormap::Viridis.sample(0.0), Color::hex(0x440154));
+    assert_eq!(Colormap::Viridis.sample(1.0), Color::hex(0xfde725));
+    assert_eq!(Colormap::Magma.sample(0.0), Color::hex(0x000004));
+    assert_eq!(Colormap::Magma.sample(1.0), Color::hex(0xfcfdbf));
+    assert_close(Colormap::Rainbow.sample(0.0), Color::hex(0xff0000));
+    assert_close(Colormap::Rainbow.sample(1.0), Color::hex(0xff00ff));
+
+    // Values outside the map are clamped
+    assert_eq!(
+        Colormap::Viridis.sample(-1.0),
+        Colormap::Viridis.sample(0.0)
+    );
+    assert_eq!(Colormap::Viridis.sample(2.0), Colormap::Viridis.sample(1.0));
+
+    // Halfway between two stops
+    assert_close(
+        Colormap::Viridis.sample(0.05),
+        Color::hex(0x440154).lerp(Color::hex(0x482475), 0.5),
+    );
+}
+
+#[test]
+fn twilight_wraps_around() {
+    let twilight = Colormap::Twilight;
+    assert_eq!(twilight.sample(0.0), twilight.sample(1.0));
+    assert_close(twilight.sample(1.25), twilight.sample(0.25));
+    assert_close(twilight.sample(-0.25), twilight.sample(0.75));
+    assert_eq!("twilight".parse(), Ok(Colormap::Twilight));
+    assert!("Twilight".parse::<Colormap>().is_err());
+}
@@ -0,0 +1,25 @@
+//! Helpers shared by the integration tests.
+
+use std::cell::Cell;
+
+use rust_wasm_double_pendulum::{
+    integrator::StepStats,
+    sim::{FrameCtx, Simulation, UpdateCtx},
+};
+
+/// Advances a simulation by `steps` fixed steps of `dt` seconds.
+pub fn run(sim: &mut impl Simulation, steps: u32, dt: f64) {
+    for frame in 0..steps {
+        let frame = FrameCtx {
+            frame,
+            dt,
+            ts: frame as f64 * dt,
+        };
+        let update = UpdateCtx {
+            frame: &frame,
+            dt,
+            steps: Cell::new(StepStats::default()),
+        };
+        sim.update(&update);
+    }
+}
@@ -0,0 +1,275 @@
+mod common;
+
+use std::cell::Cell;
+use std::f64::consts::{PI, TAU};
+
+use rust_wasm_double_pendulum::{
+    chain_pendulum::{ChainPendulum, ChainPendulumParams, Link},
+    consts::GRAVITY,
+    diagnostics::Diagnostics,
+    double_pendulum::{DoublePendulum, DoublePendulumParams},
+    ensemble::Ensemble,
+    friction::Friction,
+    integrator::{self, AdaptiveDormandPrince, Dynamics, Integrator, Rk4, StepStats},
+    lyapunov::Lyapunov,
+    pendulum::{Pendulum, PendulumParams},
+    phase::PhasePlot,
+    poincare::PoincareSection,
+    record::Observe,
+    sim::{FrameCtx, Simulation, UpdateCtx},
+};
+
+/// Physics time step used throughout, matching the browser default.
+const DT: f64 = 1.0 / 240.0;
+
+/// Advances a simulation by `steps` fixed steps.
+fn run(sim: &mut impl Simulation, steps: u32) {
+    common::run(sim, steps, DT);
+}
+
+#[test]
+fn double_pendulum_conserves_energy() {
+    let mut sim = DoublePendulum::new(DoublePendulumParams::default(), [PI / 2.0, 0.0, PI, 0.0]);
+    run(&mut sim, 2400);
+    assert!(
+        sim.energy_drift().abs() < 1e-5,
+        "drift {}",
+        sim.energy_drift()
+    );
+}
+
+#[test]
+fn adaptive_integrator_meets_tolerance() {
+    let params = DoublePendulumParams::default();
+    let initial = [PI / 2.0, 0.0, PI, 0.0];
+    let mut adaptive = AdaptiveDormandPrince::new(1e-10, 1e-10).unwrap();
+    let (mut state, mut reference) = (initial, initial);
+    let mut stats = StepStats::default();
+    for _ in 0..480 {
+        stats += adaptive.step(&params, &mut state, DT);
+        for _ in 0..16 {
+            Rk4.step(&params, &mut reference, DT / 16.0);
+        }
+    }
+    // Chaotic motion needs several internal steps per frame step
+    assert!(stats.accepted > 480, "{stats:?}");
+    for (a, b) in state.iter().zip(reference) {
+        assert!((a - b).abs() < 1e-6, "{a} != {b}");
+    }
+}
+
+#[test]
+fn adaptive_integrator_stops_on_non_finite_state() {
+    /// Dynamics that blow up, like a pendulum with a zero length.
+    struct Singular;
+    impl Dynamics for Singular {
+        fn derivative(&self, _: &[f64], out: &mut [f64]) {
+            out.fill(f64::NAN);
+        }
+    }
+    let mut adaptive = AdaptiveDormandPrince::new(1e-9, 1e-9).unwrap();
+    let mut state = [1.0, 0.0];
+    let stats = adaptive.step(&Singular, &mut state, DT);
+    assert_eq!(stats, StepStats::SINGLE);
+    assert!(state.iter().all(|x| x.is_nan()));
+
+    // Zero tolerances would force the smallest possible steps
+    assert!(AdaptiveDormandPrince::new(0.0, 0.0).is_none());
+    assert!(AdaptiveDormandPrince::new(1e-9, f64::NAN).is_none());
+    assert!(integrator::by_name("rk45-adaptive", 0.0, 1e-9).is_none());
+    assert!(integrator::by_name("rk4", 0.0, 0.0).is_some());
+}
+
+#[test]
+fn pendulum_small_angle_period() {
+    let params = PendulumParams::default();
+    let mut sim = Pendulum::new(params, [0.01, 0.0]);
+    let period = TAU * (params.length / GRAVITY).sqrt();
+    run(&mut sim, (period / DT).round() as u32);
+    let [theta, _] = sim.state();
+    assert!((theta - 0.01).abs() < 1e-4, "theta {theta}");
+}
+
+#[test]
+fn pendulum_friction_dissipates_energy() {
+    let params = PendulumParams {
+        friction: Friction {
+            viscous: 0.1,
+            coulomb: 0.01,
+            drag: 0.05,
+        },
+        ..Default::default()
+    };
+    let mut sim = Pendulum::new(params, [PI / 2.0, 0.0]);
+    run(&mut sim, 2400);
+    assert!(sim.total_energy() < sim.initial_energy());
+}
+
+#[test]
+fn two_link_chain_matches_double_pendulum() {
+    let state = [PI / 2.0, 0.0, PI / 3.0, 1.0];
+    let mut double = DoublePendulum::new(DoublePendulumParams::default(), state);
+    let params = ChainPendulumParams {
+        links: vec![Link::default(); 2],
+    };
+    let mut chain = ChainPendulum::new(params, state.to_vec());
+    run(&mut double, 480);
+    run(&mut chain, 480);
+    for (a, b) in double.state().iter().zip(chain.state()) {
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+}
+
+#[test]
+fn lyapunov_exponent_separates_chaos_from_regular_motion() {
+    let exponent = |state| {
+        let mut sim = DoublePendulum::new(DoublePendulumParams::default(), state)
+            .lyapunov(Lyapunov::default());
+        run(&mut sim, 240 * 30);
+        sim.lyapunov_exponent().unwrap()
+    };
+    let chaotic = exponent([PI / 2.0, 0.0, PI, 0.0]);
+    let regular = exponent([0.1, 0.0, 0.1, 0.0]);
+    assert!(chaotic > 0.5, "chaotic {chaotic}");
+    assert!(regular.abs() < 0.1, "regular {regular}");
+}
+
+#[test]
+fn ensemble_steps_in_lockstep_and_diverges() {
+    let state = [PI / 2.0, 0.0, PI, 0.0];
+    let mut ensemble = Ensemble::new(
+        DoublePendulumParams::default(),
+        state,
+        10,
+        [1e-9, 0.0, 1e-9, 0.0],
+    );
+    let mut single = DoublePendulum::new(DoublePendulumParams::default(), state);
+    let spread = |ensemble: &Ensemble| {
+        let [first, .., last] = ensemble.members() else {
+            unreachable!()
+        };
+        (first.state()[2] - last.state()[2]).abs()
+    };
+    run(&mut ensemble, 240);
+    run(&mut single, 240);
+    assert_eq!(ensemble.members()[0].state(), single.state());
+    assert!(spread(&ensemble) < 1e-6, "spread {}", spread(&ensemble));
+    run(&mut ensemble, 240 * 29);
+    assert!(spread(&ensemble) > 1e-2, "spread {}", spread(&ensemble));
+
+    // Step counts are the reference member's, not summed over members
+    let frame = FrameCtx {
+        frame: 0,
+        dt: DT,
+        ts: 0.0,
+    };
+    let update = UpdateCtx {
+        frame: &frame,
+        dt: DT,
+        steps: Cell::new(StepStats::default()),
+    };
+    ensemble.update(&update);
+    assert_eq!(update.steps.get(), StepStats::SINGLE);
+}
+
+#[test]
+fn phase_plot_wraps_and_scrolls() {
+    // Fast enough to swing over the top, so the angle keeps growing
+    let mut sim = Pendulum::new(PendulumParams::default(), [0.0, 10.0]);
+    let mut plot = PhasePlot::default().length(100).wrap(true);
+    for _ in 0..480 {
+        run(&mut sim, 1);
+        plot.push(&sim.joints());
+    }
+    let [path] = plot.paths() else {
+        panic!("expected one joint");
+    };
+    assert_eq!(path.len(), 100);
+    assert!(sim.state()[0] > PI);
+    assert!(path.iter().all(|&(theta, _)| (-PI..PI).contains(&theta)));
+}
+
+#[test]
+fn poincare_crossings_are_interpolated_within_steps() {
+    // Low energy, so the motion is regular and both runs stay together
+    let params = DoublePendulumParams::default();
+    let energy = -28.0;
+    let section = |substeps: u32| {
+        let mut section = PoincareSection::new(params, energy);
+        let state = section.initial_state(0.3, 0.0).unwrap();
+        let mut sim = DoublePendulum::new(params, state);
+        let dt = DT / substeps as f64;
+        section.observe(0.0, sim.state());
+        for step in 1..=4800 * substeps {
+            common::run(&mut sim, 1, dt);
+            section.observe(step as f64 * dt, sim.state());
+        }
+        section
+    };
+    let (coarse, fine) = (section(1), section(16));
+    let state = coarse.initial_state(0.3, 0.0).unwrap();
+    assert!(
+        (params.kinetic_energy(&state) + params.potential_energy(&state) - energy).abs() < 1e-12
+    );
+
+    // Roughly one crossing per period of the first rod
+    assert!(coarse.crossings().len() >= 5);
+    assert_eq!(coarse.crossings().len(), fine.crossings().len());
+    for (a, b) in coarse.crossings().iter().zip(fine.crossings()) {
+        assert_eq!(a.orbit, 0);
+        assert!((a.t - b.t).abs() < 1e-6, "{a:?} != {b:?}");
+        assert!((a.theta_2 - b.theta_2).abs() < 1e-6, "{a:?} != {b:?}");
+        assert!((a.omega_2 - b.omega_2).abs() < 1e-6, "{a:?} != {b:?}");
+    }
+}
+
+#[test]
+fn poincare_section_ends_orbits_off_its_energy() {
+    let params = DoublePendulumParams::default();
+    let mut section = PoincareSection::new(params, -28.0);
+    let state = section.initial_state(0.3, 0.0).unwrap();
+
+    // Friction drains the pendulum off the section's energy
+    let damped = DoublePendulumParams {
+        friction_1: Friction {
+            viscous: 0.5,
+            ..Friction::default()
+        },
+        ..params
+    };
+    let mut sim = DoublePendulum::new(damped, state);
+    section.observe(0.0, sim.state());
+    for step in 1..=4800 {
+        run(&mut sim, 1);
+        section.observe(step as f64 * DT, sim.state());
+    }
+    let damped_crossings = section.crossings().len();
+    assert!(damped_crossings <= 1, "{:?}", section.crossings());
+
+    // A state moved off the energy, as by a drag, ends the orbit
+    let mut sim = DoublePendulum::new(params, state);
+    section.next_orbit();
+    section.observe(0.0, sim.state());
+    for step in 1..=4800 {
+        run(&mut sim, 1);
+        let t = step as f64 * DT;
+        if step == 2400 {
+            section.observe(t, [0.5, 0.0, 0.0, 0.0]);
+        } else {
+            section.observe(t, sim.state());
+        }
+    }
+    let orbits: Vec<usize> = section.crossings()[damped_crossings..]
+        .iter()
+        .map(|c| c.orbit)
+        .collect();
+    assert!(orbits.contains(&1) && orbits.contains(&2), "{orbits:?}");
+    assert!(orbits.is_sorted(), "{orbits:?}");
+
+    // Weightless or zero length rods have no state at any energy
+    let zero = DoublePendulumParams {
+        length_1: 0.0,
+        ..params
+    };
+    assert!(zero.state_at_energy(1.0, 0.0, 0.0).is_none());
+}
@@ -0,0 +1,136 @@
+//! Trajectory serialization, read back and compared with the recorded samples.
+
+mod common;
+
+use std::f64::consts::PI;
+
+use rust_wasm_double_pendulum::{
+    double_pendulum::{DoublePendulum, DoublePendulumParams},
+    record::{Format, Joint, Recorder, Sample},
+};
+
+/// Physics time step used throughout, matching the browser default.
+const DT: f64 = 1.0 / 240.0;
+
+/// Records a double pendulum for a few steps.
+fn recorder() -> Recorder {
+    let mut sim = DoublePendulum::new(DoublePendulumParams::default(), [PI / 2.0, 0.0, PI, 0.0]);
+    let mut recorder = Recorder::new(10);
+    recorder.record(0.0, &sim);
+    for step in 1..=4 {
+        common::run(&mut sim, 1, DT);
+        recorder.record(step as f64 * DT, &sim);
+    }
+    recorder
+}
+
+/// A sample whose values JSON can't represent.
+fn non_finite() -> Sample {
+    Sample {
+        t: 1.0,
+        joints: vec![Joint {
+            theta: f64::NAN,
+            omega: f64::INFINITY,
+            x: 0.5,
+            y: -0.25,
+        }],
+        energy: f64::NEG_INFINITY,
+    }
+}
+
+/// Flattens a sample into the column order of `Sample::csv_header`.
+fn columns(sample: &Sample) -> Vec<f64> {
+    let mut values = vec![sample.t];
+    for joint in &sample.joints {
+        values.extend([joint.theta, joint.omega, joint.x, joint.y]);
+    }
+    values.push(sample.energy);
+    values
+}
+
+/// Reads the numbers of a JSON Lines sample, in the column order of
+/// `Sample::csv_header`, with `null` read as `None`.
+fn parse_json(line: &str) -> Vec<Option<f64>> {
+    let field = |key: &str| -> Vec<Option<f64>> {
+        let start = line.find(&format!(r#""{key}":"#)).unwrap() + key.len() + 3;
+        let rest = &line[start..];
+        let value = match rest.strip_prefix('[') {
+            Some(array) => &array[..array.find(']').unwrap()],
+            None => &rest[..rest.find([',', '}']).unwrap()],
+        };
+        value
+            .split(',')
+            .map(|v| (v != "null").then(|| v.parse().unwrap()))
+            .collect()
+    };
+    let (theta, omega, x, y) = (field("theta"), field("omega"), field("x"), field("y"));
+    let mut values = field("t");
+    for i in 0..theta.len() {
+        values.extend([theta[i], omega[i], x[i], y[i]]);
+    }
+    values.extend(field("energy"));
+    values
+}
+
+#[test]
+fn csv_round_trips() {
+    let recorder = recorder();
+    let csv = recorder.serialize(Format::Csv);
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some(Sample::csv_header(2).as_str()));
+    let samples: Vec<_> = recorder.samples().collect();
+    assert_eq!(samples.len(), 5);
+    for (line, sample) in lines.zip(samples) {
+        let values: Vec<f64> = line.split(',').map(|v| v.parse().unwrap()).collect();
+        assert_eq!(values, columns(sample));
+    }
+
+    // Non-finite values survive as `NaN` and `inf`
+    let values: Vec<f64> = non_finite()
+        .to_csv()
+        .split(',')
+        .map(|v| v.parse().unwrap())
+        .collect();
+    assert!(values[1].is_nan());
+    assert_eq!(values[2..], [f64::INFINITY, 0.5, -0.25, f64::NEG_INFINITY]);
+}
+
+#[test]
+fn json_lines_round_trip() {
+    let recorder = recorder();
+    let json = recorder.serialize(Format::JsonLines);
+    let samples: Vec<_> = recorder.samples().collect();
+    assert_eq!(json.lines().count(), samples.len());
+    for (line, sample) in json.lines().zip(samples) {
+        let expected: Vec<_> = columns(sample).into_iter().map(Some).collect();
+        assert_eq!(parse_json(line), expected);
+    }
+
+    // Non-finite values become `null`
+    let line = non_finite().to_json();
+    assert_eq!(
+        line,
+        r#"{"t":1,"theta":[null],"omega":[null],"x":[0.5],"y":[-0.25],"energy":null}"#
+    );
+    assert_eq!(
+        parse_json(&line),
+        [Some(1.0), None, None, Some(0.5), Some(-0.25), None]
+    );
+}
+
+#[test]
+fn recorder_keeps_the_latest_samples() {
+    let sim = DoublePendulum::new(DoublePendulumParams::default(), [0.0; 4]);
+    let times = |recorder: &Recorder| -> Vec<f64> { recorder.samples().map(|s| s.t).collect() };
+
+    let mut recorder = Recorder::new(2);
+    for t in [0.0, 1.0, 2.0] {
+        recorder.record(t, &sim);
+    }
+    assert_eq!(times(&recorder), [1.0, 2.0]);
+
+    // A zero capacity keeps nothing
+    let mut recorder = Recorder::new(0);
+    recorder.record(0.0, &sim);
+    assert!(times(&recorder).is_empty());
+}
@@ -0,0 +1,164 @@
+//! Short double pendulum trajectories compared against reference values.
+//!
+//! The reference states were computed independently of this crate, from
+//! the Lagrangian in mass-matrix form, with a 30-digit Taylor series
+//! integrator (mpmath `odefun`), at `g = 9.81`. Angles are absolute.
+
+mod common;
+
+use std::f64::consts::PI;
+
+use rust_wasm_double_pendulum::{
+    double_pendulum::{
+        AngleConvention, DoublePendulum, DoublePendulumParams, State, absolute_to_relative,
+        relative_to_absolute,
+    },
+    record::Observe,
+};
+
+/// Physics time step, small enough for `Rk4` to match the references closely.
+const DT: f64 = 1.0 / 2400.0;
+
+/// Largest accepted difference from the reference, per state component.
+const TOLERANCE: f64 = 1e-8;
+
+/// Equal 1m rods with 1kg bobs, released from `(PI / 2, 0, PI, 0)`.
+const EQUAL: [(f64, State); 3] = [
+    (
+        0.5,
+        [
+            0.35866116536070153,
+            -4.257043132839653,
+            2.431781249538826,
+            -5.1216895743262585,
+        ],
+    ),
+    (
+        1.0,
+        [
+            0.10346787907307198,
+            -2.8796953049392795,
+            -1.6865968117351717,
+            -6.965958367168475,
+        ],
+    ),
+    (
+        2.0,
+        [
+            -0.9499974601530038,
+            3.0998420445646584,
+            -2.4778124369475525,
+            2.658035003440543,
+        ],
+    ),
+];
+
+/// A 1m rod with a 1kg bob and a 0.5m rod with a 2kg bob,
+/// released from `(PI / 4, 0, -PI / 3, 1)`.
+const UNEQUAL: [(f64, State); 3] = [
+    (
+        0.5,
+        [
+            -0.27348816289508515,
+            -1.8582059341646968,
+            1.2607176826515194,
+            1.7968674702201752,
+        ],
+    ),
+    (
+        1.0,
+        [
+            -0.2611343948829533,
+            3.1987271388615697,
+            -0.7232259699645518,
+            -9.288431382076887,
+        ],
+    ),
+    (
+        2.0,
+        [
+            -0.15855093153242436,
+            0.47066022645845923,
+            1.7869308121214622,
+            2.5255951871414637,
+        ],
+    ),
+];
+
+/// Parameters of the `UNEQUAL` reference.
+fn unequal_params() -> DoublePendulumParams {
+    DoublePendulumParams {
+        length_2: 0.5,
+        mass_2: 2.0,
+        ..DoublePendulumParams::default()
+    }
+}
+
+/// Runs the simulation through the reference times, checking the
+/// state returned by `map` from the simulation against each reference.
+fn check(
+    mut sim: DoublePendulum,
+    reference: &[(f64, State)],
+    map: impl Fn(&DoublePendulum) -> State,
+) {
+    let mut t = 0.0;
+    for &(t_ref, expected) in reference {
+        common::run(&mut sim, ((t_ref - t) / DT).round() as u32, DT);
+        t = t_ref;
+        for (i, (a, b)) in map(&sim).iter().zip(expected).enumerate() {
+            assert!(
+                (a - b).abs() < TOLERANCE,
+                "component {i} at t = {t}: {a} != {b}"
+            );
+        }
+    }
+}
+
+#[test]
+fn equal_rods_match_reference() {
+    let sim = DoublePendulum::new(DoublePendulumParams::default(), [PI / 2.0, 0.0, PI, 0.0]);
+    check(sim, &EQUAL, DoublePendulum::state);
+}
+
+#[test]
+fn unequal_rods_match_reference() {
+    let sim = DoublePendulum::new(unequal_params(), [PI / 4.0, 0.0, -PI / 3.0, 1.0]);
+    check(sim, &UNEQUAL, DoublePendulum::state);
+}
+
+#[test]
+fn relative_convention_matches_reference() {
+    let initial = absolute_to_relative([PI / 4.0, 0.0, -PI / 3.0, 1.0]);
+    let sim = DoublePendulum::with_convention(unequal_params(), initial, AngleConvention::Relative);
+    check(sim, &UNEQUAL, |sim| relative_to_absolute(sim.state()));
+}
+
+#[test]
+fn bob_positions_match_reference() {
+    let params = unequal_params();
+    let sim = DoublePendulum::new(params, [PI / 4.0, 0.0, -PI / 3.0, 1.0]);
+    let reference = UNEQUAL.map(|(t, [theta_1, _, theta_2, _])| {
+        let x = params.length_1 * theta_1.sin() + params.length_2 * theta_2.sin();
+        let y = params.length_1 * theta_1.cos() + params.length_2 * theta_2.cos();
+        (t, [x, y, 0.0, 0.0])
+    });
+    check(sim, &reference, |sim| {
+        let bob = sim.joints()[1];
+        [bob.x, bob.y, 0.0, 0.0]
+    });
+}
+
+#[test]
+fn angle_conventions_round_trip() {
+    let absolute = [0.3, -1.2, 2.5, 0.7];
+    let relative = AngleConvention::Relative.from_absolute(absolute);
+    assert_eq!(relative, [0.3, -1.2, 2.2, 1.9]);
+    for (a, b) in AngleConvention::Relative
+        .to_absolute(relative)
+        .iter()
+        .zip(absolute)
+    {
+        assert!((a - b).abs() < 1e-15);
+    }
+    assert_eq!(AngleConvention::Absolute.from_absolute(absolute), absolute);
+}